@@ -0,0 +1,412 @@
+//! A builder for configuring a [Logger] with typed setters.
+//!
+//! [LoggerBuilder] is an alternative to passing [Options] to [cfg()](Logger::cfg): each setting
+//! has its own method, and [build()](LoggerBuilder::build) opens the log file, if any, and
+//! returns the configured logger. Once built, the logger's settings can be read back with its
+//! getters, such as [shows_index()](Logger::shows_index), and changed with [Options].
+//!
+//! With the `async` feature, `build_async()` builds an `AsyncLogger` instead.
+//!
+//! # Example
+//! ```rust
+//!  use forestry::prelude::*;
+//!  let mut log = Logger::builder()
+//!      .symbol(false)
+//!      .timer()
+//!      .level(LogLevel::Debug)
+//!      .build()
+//!      .unwrap();
+//!  assert!(!log.shows_symbol());
+//!  log.debug("debug");   // Output: [0000](0.002ms) debug
+//! ```
+
+use std::fs::File;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "async")]
+use crate::logs::AsyncLogger;
+use crate::logs::{Clock, ColorMode, Format, LogLevel, Logger, Options, Radix, Rotation, Stream, WriteFailure};
+use crate::sink::Sink;
+use crate::theme::Theme;
+
+/// Where the built logger writes its log file.
+enum FileTarget {
+    Default,
+    File(File),
+    Path(PathBuf),
+    Append(PathBuf),
+}
+
+/**
+    Builds a [Logger] from typed settings.
+
+    Every setting starts at the same default as [Logger::new()].
+ */
+pub struct LoggerBuilder {
+    index: bool,
+    symbol: bool,
+    color: bool,
+    bold: bool,
+    color_mode: ColorMode,
+    theme: Theme,
+    pattern: Option<String>,
+    wide_index: bool,
+    index_width: usize,
+    index_radix: Radix,
+    start_index: u64,
+    file: Option<FileTarget>,
+    file_only: bool,
+    timer: Option<Instant>,
+    timestamp: Option<Clock>,
+    timestamp_format: Option<String>,
+    max_file_size: Option<u64>,
+    rotation: Rotation,
+    keep_files: usize,
+    console: Stream,
+    stderr_format: Format,
+    file_format: Format,
+    stderr_level: LogLevel,
+    file_level: LogLevel,
+    write_failure: WriteFailure,
+    flush_level: LogLevel,
+    flush_interval: Option<Duration>,
+    sinks: Vec<(Box<dyn Sink>, LogLevel, Format)>,
+}
+
+impl LoggerBuilder {
+    /// A builder with every setting at its default.
+    pub fn new() -> Self {
+        LoggerBuilder {
+            index: true,
+            symbol: true,
+            color: true,
+            bold: true,
+            color_mode: ColorMode::Auto,
+            theme: Theme::classic(),
+            pattern: None,
+            wide_index: false,
+            index_width: 4,
+            index_radix: Radix::Hex,
+            start_index: 0,
+            file: None,
+            file_only: false,
+            timer: None,
+            timestamp: None,
+            timestamp_format: None,
+            max_file_size: None,
+            rotation: Rotation::Never,
+            keep_files: 5,
+            console: Stream::Stderr,
+            stderr_format: Format::Text,
+            file_format: Format::Text,
+            stderr_level: LogLevel::Info,
+            file_level: LogLevel::Info,
+            write_failure: WriteFailure::Stderr,
+            flush_level: LogLevel::Error,
+            flush_interval: None,
+            sinks: Vec::new(),
+        }
+    }
+
+    /// Show the log index in the header (default `true`).
+    pub fn index(mut self, show: bool) -> Self {
+        self.index = show;
+        self
+    }
+
+    /// Show the level symbol in the header (default `true`).
+    pub fn symbol(mut self, show: bool) -> Self {
+        self.symbol = show;
+        self
+    }
+
+    /// Colour lines (default `true`).
+    pub fn color(mut self, show: bool) -> Self {
+        self.color = show;
+        self
+    }
+
+    /// Make lines bold (default `true`).
+    pub fn bold(mut self, show: bool) -> Self {
+        self.bold = show;
+        self
+    }
+
+    /// Set when escape sequences are written to the console (default [ColorMode::Auto]).
+    pub fn color_mode(mut self, mode: ColorMode) -> Self {
+        self.color_mode = mode;
+        self
+    }
+
+    /// Set the colours and symbols of each level (default [Theme::classic]).
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Lay out text lines with a pattern; see [Options::Pattern].
+    pub fn pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.pattern = Some(pattern.into());
+        self
+    }
+
+    /// Use a 64-bit log index (default `false`).
+    pub fn wide_index(mut self, wide: bool) -> Self {
+        self.wide_index = wide;
+        self
+    }
+
+    /// Pad the log index with zeroes to the given number of digits (default 4).
+    pub fn index_width(mut self, width: usize) -> Self {
+        self.index_width = width;
+        self
+    }
+
+    /// Write the log index in the given radix (default [Radix::Hex]).
+    pub fn index_radix(mut self, radix: Radix) -> Self {
+        self.index_radix = radix;
+        self
+    }
+
+    /// Start the log index at the given value (default 0).
+    pub fn start_index(mut self, index: u64) -> Self {
+        self.start_index = index;
+        self
+    }
+
+    /// Log to the default file, `forestry.log`.
+    pub fn file(mut self) -> Self {
+        self.file = Some(FileTarget::Default);
+        self
+    }
+
+    /// Log to an open file.
+    pub fn file_at(mut self, file: File) -> Self {
+        self.file = Some(FileTarget::File(file));
+        self
+    }
+
+    /// Log to the file at a path, replacing its contents.
+    pub fn file_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.file = Some(FileTarget::Path(path.into()));
+        self
+    }
+
+    /// Log to the end of the file at a path.
+    pub fn file_append(mut self, path: impl Into<PathBuf>) -> Self {
+        self.file = Some(FileTarget::Append(path.into()));
+        self
+    }
+
+    /// Log only to the file, and not to the console (default `false`).
+    pub fn file_only(mut self, only: bool) -> Self {
+        self.file_only = only;
+        self
+    }
+
+    /// Show the time elapsed since this method is called.
+    pub fn timer(self) -> Self {
+        self.timer_at(Instant::now())
+    }
+
+    /// Show the time elapsed since the given instant.
+    pub fn timer_at(mut self, start: Instant) -> Self {
+        self.timer = Some(start);
+        self
+    }
+
+    /// Show the wall-clock time, in UTC or the local time zone, as RFC 3339 by default.
+    pub fn timestamp(mut self, clock: Clock) -> Self {
+        self.timestamp = Some(clock);
+        self
+    }
+
+    /// Format the wall-clock time with a `strftime`-style format string; implies [timestamp](LoggerBuilder::timestamp).
+    pub fn timestamp_format(mut self, format: impl Into<String>) -> Self {
+        self.timestamp_format = Some(format.into());
+        self
+    }
+
+    /// Rotate the log file before it grows past the given number of bytes.
+    pub fn max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = Some(bytes);
+        self
+    }
+
+    /// Rotate the log file every hour or day (default [Rotation::Never]).
+    pub fn rotate(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Keep at most the given number of rotated log files (default 5).
+    pub fn keep_files(mut self, n: usize) -> Self {
+        self.keep_files = n;
+        self
+    }
+
+    /// Set the console stream(s) written to (default [Stream::Stderr]).
+    pub fn console(mut self, stream: Stream) -> Self {
+        self.console = stream;
+        self
+    }
+
+    /// Set the output format for the console (default [Format::Text]).
+    pub fn stderr_format(mut self, format: Format) -> Self {
+        self.stderr_format = format;
+        self
+    }
+
+    /// Set the output format for the file (default [Format::Text]).
+    pub fn file_format(mut self, format: Format) -> Self {
+        self.file_format = format;
+        self
+    }
+
+    /// Set the minimum level for both the console and the file (default [LogLevel::Info]).
+    pub fn level(mut self, lvl: LogLevel) -> Self {
+        self.stderr_level = lvl;
+        self.file_level = lvl;
+        self
+    }
+
+    /// Set the minimum level for the console (default [LogLevel::Info]).
+    pub fn stderr_level(mut self, lvl: LogLevel) -> Self {
+        self.stderr_level = lvl;
+        self
+    }
+
+    /// Set the minimum level for the file (default [LogLevel::Info]).
+    pub fn file_level(mut self, lvl: LogLevel) -> Self {
+        self.file_level = lvl;
+        self
+    }
+
+    /// Set what happens when a line cannot be written (default [WriteFailure::Stderr]).
+    pub fn on_write_failure(mut self, policy: WriteFailure) -> Self {
+        self.write_failure = policy;
+        self
+    }
+
+    /// Flush the file and sinks after every message at or above the given level (default [LogLevel::Error]).
+    pub fn flush_level(mut self, lvl: LogLevel) -> Self {
+        self.flush_level = lvl;
+        self
+    }
+
+    /// Flush the file and sinks after a message if they were last flushed at least this long ago.
+    pub fn flush_interval(mut self, interval: Duration) -> Self {
+        self.flush_interval = Some(interval);
+        self
+    }
+
+    /// Add a sink; see [Logger::add_sink].
+    pub fn sink<S: Sink + 'static>(mut self, sink: S, lvl: LogLevel, format: Format) -> Self {
+        self.sinks.push((Box::new(sink), lvl, format));
+        self
+    }
+
+    /// The options which configure a new logger with these settings.
+    fn options(&self) -> Vec<Options<'_>> {
+        let mut opts = vec![
+            Options::UseTheme(&self.theme),
+            Options::IndexWidth(self.index_width),
+            Options::IndexRadix(self.index_radix),
+            Options::KeepFiles(self.keep_files),
+            Options::Rotate(self.rotation),
+            Options::Console(self.console),
+            Options::StderrFormat(self.stderr_format),
+            Options::FileFormat(self.file_format),
+            Options::StderrLevel(self.stderr_level),
+            Options::FileLevel(self.file_level),
+            Options::OnWriteFailure(self.write_failure),
+            Options::FlushLevel(self.flush_level),
+        ];
+        for (on, opt) in [
+            (!self.index, Options::NoIndex),
+            (!self.symbol, Options::NoSymbol),
+            (!self.color, Options::NoColor),
+            (!self.bold, Options::NoBold),
+            (self.wide_index, Options::WideIndex),
+            (self.file_only, Options::FileOnly),
+        ] {
+            if on {
+                opts.push(opt);
+            }
+        }
+        match self.color_mode {
+            ColorMode::Auto => {},
+            ColorMode::Always => opts.push(Options::ColorAlways),
+            ColorMode::Never => opts.push(Options::ColorNever),
+        }
+        if let Some(p) = &self.pattern {
+            opts.push(Options::Pattern(p));
+        }
+        if let Some(n) = self.max_file_size {
+            opts.push(Options::MaxFileSize(n));
+        }
+        if let Some(file) = &self.file {
+            opts.push(match file {
+                FileTarget::Default => Options::File,
+                FileTarget::File(f) => Options::FileAt(f),
+                FileTarget::Path(p) => Options::FilePath(p),
+                FileTarget::Append(p) => Options::FileAppend(p),
+            });
+        }
+        if let Some(t) = &self.timer {
+            opts.push(Options::TimerAt(t));
+        }
+        if let Some(d) = self.flush_interval {
+            opts.push(Options::FlushInterval(d));
+        }
+        if let Some(c) = self.timestamp {
+            opts.push(Options::Timestamp(c));
+        }
+        if let Some(f) = &self.timestamp_format {
+            opts.push(Options::TimestampFormat(f));
+        }
+        opts
+    }
+
+    /**
+        Build the logger, opening its log file if one was set.
+
+        Fails if the log file cannot be opened, or if the pattern or timestamp format
+        is invalid.
+     */
+    pub fn build(mut self) -> Result<Logger, io::Error> {
+        let sinks = std::mem::take(&mut self.sinks);
+        let mut log = Logger::new();
+        log.cfg(&self.options())?;
+        log.set_index(self.start_index);
+        for (sink, lvl, format) in sinks {
+            log.add_sink(sink, lvl, format);
+        }
+        Ok(log)
+    }
+
+    /**
+        Build an [AsyncLogger], opening its log file if one was set.
+
+        Requires the `async` feature. Fails as [build()](LoggerBuilder::build) does.
+     */
+    #[cfg(feature = "async")]
+    pub async fn build_async(mut self) -> Result<AsyncLogger, io::Error> {
+        let sinks = std::mem::take(&mut self.sinks);
+        let mut log = AsyncLogger::new();
+        log.cfg(&self.options()).await?;
+        log.set_index(self.start_index);
+        for (sink, lvl, format) in sinks {
+            log.add_sink(sink, lvl, format);
+        }
+        Ok(log)
+    }
+}
+
+impl Default for LoggerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
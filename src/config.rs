@@ -0,0 +1,577 @@
+//! Configuration from a TOML or JSON file.
+//!
+//! Requires the `config` feature. A [Config] describes a logger's levels, formats, theme,
+//! log file, rotation and extra sinks, and is usually loaded from a `forestry.toml` with
+//! [Logger::from_config_file()]. Every key is optional and defaults to the same value as
+//! [Logger::new()].
+//!
+//! ```toml
+//! level = "info"             # or stderr_level / file_level
+//! format = "text"            # or stderr_format / file_format
+//! color = "auto"
+//! console = "stderr"
+//! theme = "colorblind"       # classic, colorblind or emoji
+//! timestamp = "utc"
+//! pattern = "{time} {level:<5} {target}: {msg}"
+//! on_write_failure = "stderr" # ignore, stderr or panic
+//! flush_level = "error"
+//! flush_interval_ms = 1000
+//!
+//! [file]
+//! path = "logs/app.log"
+//! append = true              # default true
+//! format = "json"
+//! max_size = 10_485_760
+//! rotate = "daily"
+//! keep = 7
+//!
+//! [[sinks]]
+//! kind = "stdout"            # stdout, stderr or file
+//! level = "warn"
+//! format = "json"
+//! ```
+//!
+//! Files ending in `.json` are read as JSON with the same keys; any other file is read as TOML.
+//! String values are case-insensitive, except for paths, patterns and timestamp formats.
+//!
+//! With the `watch` feature, the `watch` module re-applies a config file to a running
+//! logger whenever it changes.
+//!
+//! # Example
+//! ```rust
+//!  use forestry::prelude::*;
+//!  use forestry::config::Config;
+//!  let config = Config::from_toml("level = 'debug'\ntheme = 'emoji'").unwrap();
+//!  let mut log = config.builder().unwrap().build().unwrap();
+//!  log.debug("debug");   // Output: [0000:🐛] debug
+//! ```
+
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::builder::LoggerBuilder;
+#[cfg(feature = "async")]
+use crate::logs::AsyncLogger;
+use crate::logs::{ColorMode, Format, LogLevel, Logger, Options, ParseError, Stream};
+use crate::pattern::Pattern;
+use crate::theme::Theme;
+
+/**
+    An error loading or applying a config file.
+ */
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The config file, the log file or a sink's file could not be opened.
+    Io(io::Error),
+    /// The config file is not valid TOML, or has an unknown key.
+    Toml(toml::de::Error),
+    /// The config file is not valid JSON, or has an unknown key.
+    Json(serde_json::Error),
+    /// A key has an invalid value.
+    Invalid {
+        /// The name of the key.
+        key: &'static str,
+        /// Why the value is invalid.
+        error: ParseError,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "{}", e),
+            ConfigError::Toml(e) => write!(f, "invalid TOML config: {}", e),
+            ConfigError::Json(e) => write!(f, "invalid JSON config: {}", e),
+            ConfigError::Invalid { key, error } => write!(f, "{}: {}", key, error),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io(e) => Some(e),
+            ConfigError::Toml(e) => Some(e),
+            ConfigError::Json(e) => Some(e),
+            ConfigError::Invalid { error, .. } => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for ConfigError {
+    fn from(e: io::Error) -> Self {
+        ConfigError::Io(e)
+    }
+}
+
+/**
+    The settings of a logger, as read from a config file.
+
+    Unset keys keep their default. See the [module docs](self) for the file layout.
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The minimum level for the console and the file.
+    pub level: Option<String>,
+    /// The minimum level for the console.
+    pub stderr_level: Option<String>,
+    /// The minimum level for the file.
+    pub file_level: Option<String>,
+    /// The output format for the console and the file.
+    pub format: Option<String>,
+    /// The output format for the console.
+    pub stderr_format: Option<String>,
+    /// The output format for the file.
+    pub file_format: Option<String>,
+    /// When escape sequences are written to the console.
+    pub color: Option<String>,
+    /// The console stream(s) written to.
+    pub console: Option<String>,
+    /// The name of a built-in [Theme].
+    pub theme: Option<String>,
+    /// A line pattern; see [Options::Pattern].
+    pub pattern: Option<String>,
+    /// Show the log index in the header.
+    pub index: Option<bool>,
+    /// Show the level symbol in the header.
+    pub symbol: Option<bool>,
+    /// Colour lines.
+    pub colors: Option<bool>,
+    /// Make lines bold.
+    pub bold: Option<bool>,
+    /// Use a 64-bit log index.
+    pub wide_index: Option<bool>,
+    /// Pad the log index with zeroes to this many digits.
+    pub index_width: Option<usize>,
+    /// The radix of the log index.
+    pub index_radix: Option<String>,
+    /// Show the time elapsed since the logger was built.
+    pub timer: Option<bool>,
+    /// Show the wall-clock time, in `utc` or `local` time, as RFC 3339 by default.
+    pub timestamp: Option<String>,
+    /// A `strftime`-style format for the wall-clock time.
+    pub timestamp_format: Option<String>,
+    /// What happens when a line cannot be written: `ignore`, `stderr` or `panic`.
+    pub on_write_failure: Option<String>,
+    /// The level at or above which every message is followed by a flush.
+    pub flush_level: Option<String>,
+    /// Flush after a message if the outputs were last flushed at least this many milliseconds ago.
+    pub flush_interval_ms: Option<u64>,
+    /// The log file.
+    pub file: Option<FileConfig>,
+    /// Extra outputs.
+    pub sinks: Vec<SinkConfig>,
+}
+
+/**
+    The `[file]` table of a config file.
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileConfig {
+    /// The path of the log file.
+    pub path: PathBuf,
+    /// Keep the file's previous contents (default `true`).
+    #[serde(default = "yes")]
+    pub append: bool,
+    /// Log only to the file, and not to the console (default `false`).
+    #[serde(default)]
+    pub only: bool,
+    /// The minimum level for the file; overrides the top-level `file_level`.
+    #[serde(default)]
+    pub level: Option<String>,
+    /// The output format for the file.
+    #[serde(default)]
+    pub format: Option<String>,
+    /// Rotate the file before it grows past this many bytes.
+    #[serde(default)]
+    pub max_size: Option<u64>,
+    /// Rotate the file `hourly` or `daily`.
+    #[serde(default)]
+    pub rotate: Option<String>,
+    /// Keep at most this many rotated files.
+    #[serde(default)]
+    pub keep: Option<usize>,
+}
+
+/**
+    An entry of the `[[sinks]]` array of a config file.
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SinkConfig {
+    /// `stdout`, `stderr` or `file`.
+    pub kind: String,
+    /// The path of a `file` sink, which is appended to.
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+    /// The minimum level of the sink (default `info`).
+    #[serde(default)]
+    pub level: Option<String>,
+    /// The output format of the sink (default `text`).
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+fn yes() -> bool {
+    true
+}
+
+/// Parse an optional value, naming `key` if it is invalid.
+fn parse<T: FromStr<Err = ParseError>>(key: &'static str, s: &Option<String>) -> Result<Option<T>, ConfigError> {
+    s.as_deref()
+        .map(|s| s.parse().map_err(|error| ConfigError::Invalid { key, error }))
+        .transpose()
+}
+
+impl Config {
+    /// Parse a config from TOML.
+    pub fn from_toml(s: &str) -> Result<Self, ConfigError> {
+        toml::from_str(s).map_err(ConfigError::Toml)
+    }
+
+    /// Parse a config from JSON.
+    pub fn from_json(s: &str) -> Result<Self, ConfigError> {
+        serde_json::from_str(s).map_err(ConfigError::Json)
+    }
+
+    /// Read a config file, as JSON if its name ends in `.json` and as TOML otherwise.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let s = fs::read_to_string(path)?;
+        match path.extension() {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::from_json(&s),
+            _ => Self::from_toml(&s),
+        }
+    }
+
+    /**
+        A builder with these settings.
+
+        Opens the files of any `file` sinks, and fails if a value is invalid.
+     */
+    pub fn builder(&self) -> Result<LoggerBuilder, ConfigError> {
+        self.apply(LoggerBuilder::new())
+    }
+
+    /// Apply these settings on top of a builder's.
+    pub fn apply(&self, mut b: LoggerBuilder) -> Result<LoggerBuilder, ConfigError> {
+        if let Some(l) = parse::<LogLevel>("level", &self.level)? {
+            b = b.level(l);
+        }
+        if let Some(l) = parse("stderr_level", &self.stderr_level)? {
+            b = b.stderr_level(l);
+        }
+        if let Some(l) = parse("file_level", &self.file_level)? {
+            b = b.file_level(l);
+        }
+        if let Some(f) = parse("format", &self.format)? {
+            b = b.stderr_format(f).file_format(f);
+        }
+        if let Some(f) = parse("stderr_format", &self.stderr_format)? {
+            b = b.stderr_format(f);
+        }
+        if let Some(f) = parse("file_format", &self.file_format)? {
+            b = b.file_format(f);
+        }
+        if let Some(c) = parse("color", &self.color)? {
+            b = b.color_mode(c);
+        }
+        if let Some(s) = parse("console", &self.console)? {
+            b = b.console(s);
+        }
+        if let Some(t) = parse("theme", &self.theme)? {
+            b = b.theme(t);
+        }
+        if let Some(p) = self.pattern()? {
+            b = b.pattern(p);
+        }
+        for (set, f) in [
+            (self.index, LoggerBuilder::index as fn(LoggerBuilder, bool) -> LoggerBuilder),
+            (self.symbol, LoggerBuilder::symbol),
+            (self.colors, LoggerBuilder::color),
+            (self.bold, LoggerBuilder::bold),
+            (self.wide_index, LoggerBuilder::wide_index),
+        ] {
+            if let Some(on) = set {
+                b = f(b, on);
+            }
+        }
+        if let Some(w) = self.index_width {
+            b = b.index_width(w);
+        }
+        if let Some(r) = parse("index_radix", &self.index_radix)? {
+            b = b.index_radix(r);
+        }
+        if self.timer == Some(true) {
+            b = b.timer();
+        }
+        if let Some(c) = parse("timestamp", &self.timestamp)? {
+            b = b.timestamp(c);
+        }
+        if let Some(f) = &self.timestamp_format {
+            b = b.timestamp_format(f.as_str());
+        }
+        if let Some(w) = parse("on_write_failure", &self.on_write_failure)? {
+            b = b.on_write_failure(w);
+        }
+        if let Some(l) = parse("flush_level", &self.flush_level)? {
+            b = b.flush_level(l);
+        }
+        if let Some(ms) = self.flush_interval_ms {
+            b = b.flush_interval(std::time::Duration::from_millis(ms));
+        }
+        if let Some(file) = &self.file {
+            b = file.apply(b)?;
+        }
+        for sink in &self.sinks {
+            b = sink.apply(b)?;
+        }
+        Ok(b)
+    }
+
+    /// The line pattern, if it is set and valid.
+    fn pattern(&self) -> Result<Option<&str>, ConfigError> {
+        match &self.pattern {
+            Some(p) if Pattern::parse(p).is_err() => {
+                let error = ParseError { value: p.clone(), expected: "a valid pattern" };
+                Err(ConfigError::Invalid { key: "pattern", error })
+            },
+            p => Ok(p.as_deref()),
+        }
+    }
+
+    /**
+        The options which change `log` to these settings.
+
+        Only the keys that are set produce an option. A running timer is left alone, and the
+        log file is only reopened if its path or append mode differs from the open one.
+     */
+    fn options<'a>(&'a self, log: &Logger, theme: Option<&'a Theme>) -> Result<Vec<Options<'a>>, ConfigError> {
+        let mut opts = Vec::new();
+        if let Some(l) = parse("level", &self.level)? {
+            opts.push(Options::Level(l));
+        }
+        if let Some(l) = parse("stderr_level", &self.stderr_level)? {
+            opts.push(Options::StderrLevel(l));
+        }
+        if let Some(l) = parse("file_level", &self.file_level)? {
+            opts.push(Options::FileLevel(l));
+        }
+        if let Some(f) = parse("format", &self.format)? {
+            opts.extend([Options::StderrFormat(f), Options::FileFormat(f)]);
+        }
+        if let Some(f) = parse("stderr_format", &self.stderr_format)? {
+            opts.push(Options::StderrFormat(f));
+        }
+        if let Some(f) = parse("file_format", &self.file_format)? {
+            opts.push(Options::FileFormat(f));
+        }
+        if let Some(c) = parse("color", &self.color)? {
+            opts.push(match c {
+                ColorMode::Auto => Options::ColorAuto,
+                ColorMode::Always => Options::ColorAlways,
+                ColorMode::Never => Options::ColorNever,
+            });
+        }
+        let console = parse("console", &self.console)?;
+        if let Some(s) = console {
+            opts.push(Options::Console(s));
+        }
+        if let Some(t) = theme {
+            opts.push(Options::UseTheme(t));
+        }
+        if let Some(p) = self.pattern()? {
+            opts.push(Options::Pattern(p));
+        }
+        for (set, on, off) in [
+            (self.index, Options::Index, Options::NoIndex),
+            (self.symbol, Options::Symbol, Options::NoSymbol),
+            (self.colors, Options::Color, Options::NoColor),
+            (self.bold, Options::Bold, Options::NoBold),
+            (self.wide_index, Options::WideIndex, Options::NarrowIndex),
+        ] {
+            if let Some(set) = set {
+                opts.push(if set { on } else { off });
+            }
+        }
+        if let Some(w) = self.index_width {
+            opts.push(Options::IndexWidth(w));
+        }
+        if let Some(r) = parse("index_radix", &self.index_radix)? {
+            opts.push(Options::IndexRadix(r));
+        }
+        match self.timer {
+            Some(true) if !log.shows_timer() => opts.push(Options::Timer),
+            Some(false) => opts.push(Options::NoTimer),
+            _ => {},
+        }
+        if let Some(c) = parse("timestamp", &self.timestamp)? {
+            opts.push(Options::Timestamp(c));
+        }
+        if let Some(f) = &self.timestamp_format {
+            opts.push(Options::TimestampFormat(f));
+        }
+        if let Some(w) = parse("on_write_failure", &self.on_write_failure)? {
+            opts.push(Options::OnWriteFailure(w));
+        }
+        if let Some(l) = parse("flush_level", &self.flush_level)? {
+            opts.push(Options::FlushLevel(l));
+        }
+        if let Some(ms) = self.flush_interval_ms {
+            opts.push(Options::FlushInterval(std::time::Duration::from_millis(ms)));
+        }
+        if let Some(file) = &self.file {
+            file.options(log, console, &mut opts)?;
+        }
+        Ok(opts)
+    }
+
+    /**
+        Reconfigure a running logger with these settings.
+
+        Only the keys in the config are applied; every other setting, the timer, the log index
+        and any sinks are kept. The log file is only reopened if its path or `append` changed.
+        The `[[sinks]]` array is not applied again, as its sinks were added when the logger
+        was built.
+     */
+    pub fn apply_to(&self, log: &mut Logger) -> Result<(), ConfigError> {
+        let theme = parse::<Theme>("theme", &self.theme)?;
+        let opts = self.options(log, theme.as_ref())?;
+        log.cfg(&opts)?;
+        Ok(())
+    }
+
+    /**
+        Reconfigure a running asynchronous logger with these settings; see [apply_to](Config::apply_to).
+
+        An [AsyncLogger] opens its log file with `tokio`, so it cannot be given to `apply_to`:
+        ```rust,compile_fail
+         # async fn f() {
+         use forestry::prelude::*;
+         use forestry::config::Config;
+         let mut log = AsyncLogger::new();
+         Config::from_toml("[file]\npath = 'app.log'").unwrap().apply_to(&mut log).unwrap();
+         # }
+        ```
+     */
+    #[cfg(feature = "async")]
+    pub async fn apply_to_async(&self, log: &mut AsyncLogger) -> Result<(), ConfigError> {
+        let theme = parse::<Theme>("theme", &self.theme)?;
+        let opts = self.options(log.logger(), theme.as_ref())?;
+        log.cfg(&opts).await?;
+        Ok(())
+    }
+}
+
+impl FileConfig {
+    fn apply(&self, mut b: LoggerBuilder) -> Result<LoggerBuilder, ConfigError> {
+        b = match self.append {
+            true => b.file_append(&self.path),
+            false => b.file_path(&self.path),
+        };
+        b = b.file_only(self.only);
+        if let Some(l) = parse("file.level", &self.level)? {
+            b = b.file_level(l);
+        }
+        if let Some(f) = parse("file.format", &self.format)? {
+            b = b.file_format(f);
+        }
+        if let Some(n) = self.max_size {
+            b = b.max_file_size(n);
+        }
+        if let Some(r) = parse("file.rotate", &self.rotate)? {
+            b = b.rotate(r);
+        }
+        if let Some(n) = self.keep {
+            b = b.keep_files(n);
+        }
+        Ok(b)
+    }
+}
+
+impl FileConfig {
+    /// Push the options which change `log`'s file to these settings, given the `console` key.
+    fn options<'a>(&'a self, log: &Logger, console: Option<Stream>, opts: &mut Vec<Options<'a>>) -> Result<(), ConfigError> {
+        if let Some(l) = parse("file.level", &self.level)? {
+            opts.push(Options::FileLevel(l));
+        }
+        if let Some(f) = parse("file.format", &self.format)? {
+            opts.push(Options::FileFormat(f));
+        }
+        if let Some(n) = self.max_size {
+            opts.push(Options::MaxFileSize(n));
+        }
+        if let Some(r) = parse("file.rotate", &self.rotate)?.filter(|&r| r != log.rotation()) {
+            opts.push(Options::Rotate(r));
+        }
+        if let Some(n) = self.keep {
+            opts.push(Options::KeepFiles(n));
+        }
+        if log.file_path() != Some(self.path.as_path()) || log.file_appends() != self.append {
+            opts.push(match self.append {
+                true => Options::FileAppend(&self.path),
+                false => Options::FilePath(&self.path),
+            });
+        }
+        if self.only {
+            opts.push(Options::FileOnly);
+        } else if log.file_only() {
+            opts.push(Options::Console(console.unwrap_or(log.console())));
+        }
+        Ok(())
+    }
+}
+
+impl SinkConfig {
+    fn apply(&self, b: LoggerBuilder) -> Result<LoggerBuilder, ConfigError> {
+        let lvl = parse("sinks.level", &self.level)?.unwrap_or(LogLevel::Info);
+        let format = parse("sinks.format", &self.format)?.unwrap_or(Format::Text);
+        let invalid = |expected| ConfigError::Invalid {
+            key: "sinks.kind",
+            error: ParseError { value: self.kind.clone(), expected },
+        };
+        Ok(match self.kind.trim().to_lowercase().as_str() {
+            "stdout" => b.sink(io::stdout(), lvl, format),
+            "stderr" => b.sink(io::stderr(), lvl, format),
+            "file" => {
+                let path = self.path.as_ref().ok_or_else(|| invalid("a file sink with a path"))?;
+                if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+                    fs::create_dir_all(dir)?;
+                }
+                let file = OpenOptions::new().create(true).append(true).open(path)?;
+                b.sink(file, lvl, format)
+            },
+            _ => return Err(invalid("one of stdout, stderr or file")),
+        })
+    }
+}
+
+impl Logger {
+    /**
+        Build a logger from a TOML or JSON config file.
+
+        See the [config](crate::config) module for the file layout.
+     */
+    pub fn from_config_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        Ok(Config::load(path)?.builder()?.build()?)
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncLogger {
+    /**
+        Build an asynchronous logger from a TOML or JSON config file.
+
+        See the [config](crate::config) module for the file layout.
+     */
+    pub async fn from_config_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        Ok(Config::load(path)?.builder()?.build_async().await?)
+    }
+}
@@ -0,0 +1,197 @@
+//! Configuration from environment variables.
+//!
+//! [Logger::from_env()] builds a logger from the variables below, and
+//! [LoggerBuilder::env()] applies them on top of a builder's settings, so that the variables
+//! override the defaults chosen in code. Unset or empty variables are ignored.
+//!
+//! | Variable                 | Values                                                | Option            |
+//! |--------------------------|-------------------------------------------------------|-------------------|
+//! | `FORESTRY_LEVEL`         | `trace`, `debug`, `info`, `success`, `warn`, `error`, `critical` | `Level`  |
+//! | `FORESTRY_STDERR_LEVEL`  | as `FORESTRY_LEVEL`                                   | `StderrLevel`     |
+//! | `FORESTRY_FILE_LEVEL`    | as `FORESTRY_LEVEL`                                   | `FileLevel`       |
+//! | `FORESTRY_COLOR`         | `auto`, `always`, `never`                             | `ColorAuto` etc.  |
+//! | `FORESTRY_FILE`          | a path, appended to                                   | `FileAppend`      |
+//! | `FORESTRY_FORMAT`        | `text`, `json`                                        | `StderrFormat` and `FileFormat` |
+//! | `FORESTRY_STDERR_FORMAT` | as `FORESTRY_FORMAT`                                  | `StderrFormat`    |
+//! | `FORESTRY_FILE_FORMAT`   | as `FORESTRY_FORMAT`                                  | `FileFormat`      |
+//! | `FORESTRY_CONSOLE`       | `stderr`, `stdout`, `split`                           | `Console`         |
+//! | `FORESTRY_TIMESTAMP`     | `utc`, `local`                                        | `Timestamp`       |
+//! | `FORESTRY_PATTERN`       | a pattern                                             | `Pattern`         |
+//! | `FORESTRY_ON_WRITE_FAILURE` | `ignore`, `stderr`, `panic`                        | `OnWriteFailure`  |
+//!
+//! Values are case-insensitive, except for paths and patterns. An invalid value is reported
+//! as an [EnvError] naming the variable.
+//!
+//! # Example
+//! ```rust
+//!  use forestry::prelude::*;
+//!  // FORESTRY_LEVEL=debug FORESTRY_FORMAT=json ./app
+//!  let mut log = Logger::from_env().unwrap();
+//!  log.debug("debug");
+//! ```
+
+use std::ffi::OsString;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::builder::LoggerBuilder;
+#[cfg(feature = "async")]
+use crate::logs::AsyncLogger;
+use crate::logs::{LogLevel, Logger, ParseError};
+use crate::pattern::Pattern;
+
+/**
+    An error configuring a logger from environment variables.
+ */
+#[derive(Debug)]
+pub enum EnvError {
+    /// A variable is not valid Unicode.
+    NotUnicode {
+        /// The name of the variable.
+        var: &'static str,
+    },
+    /// A variable has an invalid value.
+    Invalid {
+        /// The name of the variable.
+        var: &'static str,
+        /// Why the value is invalid.
+        error: ParseError,
+    },
+    /// The log file could not be opened.
+    Io(io::Error),
+}
+
+impl fmt::Display for EnvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvError::NotUnicode { var } => write!(f, "{} is not valid unicode", var),
+            EnvError::Invalid { var, error } => write!(f, "{}: {}", var, error),
+            EnvError::Io(e) => write!(f, "failed to open log file: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for EnvError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EnvError::NotUnicode { .. } => None,
+            EnvError::Invalid { error, .. } => Some(error),
+            EnvError::Io(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for EnvError {
+    fn from(e: io::Error) -> Self {
+        EnvError::Io(e)
+    }
+}
+
+/// Reads variables through `get`, so that tests need not touch the process environment.
+struct Env<F: Fn(&str) -> Option<OsString>> {
+    get: F,
+}
+
+impl<F: Fn(&str) -> Option<OsString>> Env<F> {
+    /// The value of a variable, or `None` if it is unset or empty.
+    fn string(&self, var: &'static str) -> Result<Option<String>, EnvError> {
+        match (self.get)(var).map(OsString::into_string) {
+            None => Ok(None),
+            Some(Ok(s)) if s.is_empty() => Ok(None),
+            Some(Ok(s)) => Ok(Some(s)),
+            Some(Err(_)) => Err(EnvError::NotUnicode { var }),
+        }
+    }
+
+    /// The parsed value of a variable, or `None` if it is unset or empty.
+    fn parse<T: FromStr<Err = ParseError>>(&self, var: &'static str) -> Result<Option<T>, EnvError> {
+        self.string(var)?
+            .map(|s| s.parse().map_err(|error| EnvError::Invalid { var, error }))
+            .transpose()
+    }
+
+    fn apply(&self, mut b: LoggerBuilder) -> Result<LoggerBuilder, EnvError> {
+        if let Some(l) = self.parse::<LogLevel>("FORESTRY_LEVEL")? {
+            b = b.level(l);
+        }
+        if let Some(l) = self.parse("FORESTRY_STDERR_LEVEL")? {
+            b = b.stderr_level(l);
+        }
+        if let Some(l) = self.parse("FORESTRY_FILE_LEVEL")? {
+            b = b.file_level(l);
+        }
+        if let Some(c) = self.parse("FORESTRY_COLOR")? {
+            b = b.color_mode(c);
+        }
+        if let Some(p) = self.string("FORESTRY_FILE")? {
+            b = b.file_append(PathBuf::from(p));
+        }
+        if let Some(f) = self.parse("FORESTRY_FORMAT")? {
+            b = b.stderr_format(f).file_format(f);
+        }
+        if let Some(f) = self.parse("FORESTRY_STDERR_FORMAT")? {
+            b = b.stderr_format(f);
+        }
+        if let Some(f) = self.parse("FORESTRY_FILE_FORMAT")? {
+            b = b.file_format(f);
+        }
+        if let Some(s) = self.parse("FORESTRY_CONSOLE")? {
+            b = b.console(s);
+        }
+        if let Some(c) = self.parse("FORESTRY_TIMESTAMP")? {
+            b = b.timestamp(c);
+        }
+        if let Some(p) = self.string("FORESTRY_PATTERN")? {
+            if Pattern::parse(&p).is_err() {
+                let error = ParseError { value: p, expected: "a valid pattern" };
+                return Err(EnvError::Invalid { var: "FORESTRY_PATTERN", error });
+            }
+            b = b.pattern(p);
+        }
+        if let Some(w) = self.parse("FORESTRY_ON_WRITE_FAILURE")? {
+            b = b.on_write_failure(w);
+        }
+        Ok(b)
+    }
+}
+
+impl LoggerBuilder {
+    /**
+        Apply the `FORESTRY_*` environment variables on top of the current settings.
+
+        See the [env](mod@crate::env) module for the variables read.
+     */
+    pub fn env(self) -> Result<Self, EnvError> {
+        self.env_with(|var| std::env::var_os(var))
+    }
+
+    /// Apply variables looked up with `get` instead of from the process environment.
+    pub(crate) fn env_with(self, get: impl Fn(&str) -> Option<OsString>) -> Result<Self, EnvError> {
+        Env { get }.apply(self)
+    }
+}
+
+impl Logger {
+    /**
+        Build a logger from the `FORESTRY_*` environment variables.
+
+        See the [env](mod@crate::env) module for the variables read.
+     */
+    pub fn from_env() -> Result<Self, EnvError> {
+        Ok(LoggerBuilder::new().env()?.build()?)
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncLogger {
+    /**
+        Build an asynchronous logger from the `FORESTRY_*` environment variables.
+
+        See the [env](mod@crate::env) module for the variables read.
+     */
+    pub async fn from_env() -> Result<Self, EnvError> {
+        Ok(LoggerBuilder::new().env()?.build_async().await?)
+    }
+}
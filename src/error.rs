@@ -0,0 +1,67 @@
+//! The error returned when a log line could not be written.
+//!
+//! The logging methods, such as [info](crate::logs::Logger::info), handle failed writes with
+//! the logger's [WriteFailure](crate::logs::WriteFailure) policy, set with
+//! [Options::OnWriteFailure](crate::logs::Options::OnWriteFailure). Their `try_` variants,
+//! such as [try_info](crate::logs::Logger::try_info), return an [Error] instead.
+//!
+//! # Example
+//! ```rust
+//!  use forestry::prelude::*;
+//!  let mut log = Logger::new();
+//!  if let Err(e) = log.try_info("info") {
+//!      eprintln!("logging failed: {e}");
+//!  }
+//! ```
+
+use std::fmt;
+use std::io;
+
+/**
+    A log line could not be written to one of the logger's outputs.
+
+    Every output is still written to when one fails; the error is that of the
+    first output which failed.
+ */
+#[derive(Debug)]
+pub enum Error {
+    /// Writing to stderr or stdout failed, e.g. because the pipe was closed.
+    Console(io::Error),
+    /// Writing to or rotating the log file failed, e.g. because the disk is full.
+    File(io::Error),
+    /// Writing to a sink added with [add_sink](crate::logs::Logger::add_sink) failed.
+    Sink(io::Error),
+}
+
+impl Error {
+    /// The underlying I/O error.
+    pub fn io_error(&self) -> &io::Error {
+        match self {
+            Error::Console(e) | Error::File(e) | Error::Sink(e) => e,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Console(e) => write!(f, "failed to write to the console: {}", e),
+            Error::File(e) => write!(f, "failed to write to the log file: {}", e),
+            Error::Sink(e) => write!(f, "failed to write to a sink: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.io_error())
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::Console(e) | Error::File(e) | Error::Sink(e) => e,
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! An implementation of the [log] crate's facade backed by a [Logger].
+//!
+//! Enabled by the `log` feature. Once installed with [init()] (or one of its variants),
+//! messages emitted through `log::info!` and friends, including those from dependencies,
+//! are formatted and written exactly like messages logged on a [Logger] directly.
+//!
+//! [log::Level]s map onto [LogLevel]s of the same name, so they share the same symbols and
+//! colours.
+//!
+//! # Example
+//! ```rust
+//!  use forestry::prelude::*;
+//!  let mut log = Logger::new();
+//!  log.cfg(&[Options::Level(LogLevel::Debug)]).unwrap();
+//!  forestry::facade::init_with(log);
+//!  log::debug!("debug");        // Output: [0000:?] debug
+//! ```
+
+use std::sync::Mutex;
+
+use crate::logs::{LogLevel, Logger};
+
+impl From<log::Level> for LogLevel {
+    fn from(lvl: log::Level) -> Self {
+        match lvl {
+            log::Level::Trace => LogLevel::Trace,
+            log::Level::Debug => LogLevel::Debug,
+            log::Level::Info => LogLevel::Info,
+            log::Level::Warn => LogLevel::Warn,
+            log::Level::Error => LogLevel::Error,
+        }
+    }
+}
+
+/**
+    A [log::Log] implementation wrapping a [Logger].
+
+    The `log` crate calls it through a shared reference, so the logger sits behind a mutex,
+    which keeps working if a thread panicked while holding it. A record's arguments are only
+    formatted if its level is enabled.
+ */
+pub struct ForestryLog {
+    logger: Mutex<Logger>,
+}
+
+impl ForestryLog {
+    /// Wrap a configured logger.
+    pub fn new(logger: Logger) -> Self {
+        ForestryLog {
+            logger: Mutex::new(logger),
+        }
+    }
+
+    /// The most verbose [log::LevelFilter] that the wrapped logger will output.
+    pub fn max_level(&self) -> log::LevelFilter {
+        let logger = self.logger.lock().unwrap_or_else(|e| e.into_inner());
+        [
+            log::Level::Trace,
+            log::Level::Debug,
+            log::Level::Info,
+            log::Level::Warn,
+            log::Level::Error,
+        ]
+            .into_iter()
+            .find(|&l| logger.enabled(l.into()))
+            .map_or(log::LevelFilter::Off, |l| l.to_level_filter())
+    }
+}
+
+impl log::Log for ForestryLog {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.logger
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .enabled(metadata.level().into())
+    }
+
+    fn log(&self, record: &log::Record) {
+        let mut logger = self.logger.lock().unwrap_or_else(|e| e.into_inner());
+        let lvl = record.level().into();
+        if logger.enabled(lvl) {
+            logger.print_target(lvl, record.target(), &record.args().to_string(), &[]);
+        }
+    }
+
+    fn flush(&self) {
+        let _ = self.logger
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .flush();
+    }
+}
+
+/**
+    Install a default [Logger] as the global logger for the [log] crate.
+
+    # Panics
+    Panics if a global logger has already been installed.
+ */
+pub fn init() {
+    try_init().expect("a global logger has already been installed");
+}
+
+/**
+    Install a default [Logger] as the global logger for the [log] crate.
+
+    Returns an error if a global logger has already been installed.
+ */
+pub fn try_init() -> Result<(), log::SetLoggerError> {
+    try_init_with(Logger::new())
+}
+
+/**
+    Install the given [Logger] as the global logger for the [log] crate.
+
+    The [log] crate's maximum level is set to match the logger's minimum levels.
+
+    # Panics
+    Panics if a global logger has already been installed.
+ */
+pub fn init_with(logger: Logger) {
+    try_init_with(logger).expect("a global logger has already been installed");
+}
+
+/**
+    Install the given [Logger] as the global logger for the [log] crate.
+
+    The [log] crate's maximum level is set to match the logger's minimum levels.
+    Returns an error if a global logger has already been installed.
+ */
+pub fn try_init_with(logger: Logger) -> Result<(), log::SetLoggerError> {
+    let facade = ForestryLog::new(logger);
+    let max = facade.max_level();
+    log::set_boxed_logger(Box::new(facade))?;
+    log::set_max_level(max);
+    Ok(())
+}
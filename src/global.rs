@@ -0,0 +1,78 @@
+//! A process-wide default logger.
+//!
+//! The global logger is used by the [info!](crate::info), [warn!](crate::warn) etc. macros.
+//! A [Logger] can be installed once with [set_logger()]; otherwise, a default [Logger] is
+//! created on first use. Either way, it can be configured with [cfg()] using the same
+//! [Options] as any other logger.
+//!
+//! The global logger is always synchronous, including with the `async` feature.
+//!
+//! # Example
+//! ```rust
+//!  use forestry::prelude::*;
+//!  forestry::global::cfg(&[Options::Level(LogLevel::Debug)]).unwrap();
+//!  let n = 3;
+//!  forestry::debug!("{n} items remaining");  // Output: [0000:?] 3 items remaining
+//! ```
+
+use std::io;
+use std::sync::OnceLock;
+
+use crate::logs::{LogLevel, Logger, Options};
+use crate::shared::SharedLogger;
+
+static LOGGER: OnceLock<SharedLogger> = OnceLock::new();
+
+/**
+    Install `logger` as the global logger.
+
+    This can only be done once, before the global logger is first used;
+    otherwise, `logger` is handed back as an error.
+ */
+#[allow(clippy::result_large_err)]
+pub fn set_logger(logger: Logger) -> Result<(), Logger> {
+    let mut logger = Some(logger);
+    LOGGER.get_or_init(|| SharedLogger::new(logger.take().unwrap()));
+    match logger {
+        Some(l) => Err(l),
+        None => Ok(()),
+    }
+}
+
+/// Get the global logger, creating a default [Logger] if none is installed.
+pub fn logger() -> &'static SharedLogger {
+    LOGGER.get_or_init(|| SharedLogger::new(Logger::new()))
+}
+
+/**
+    Configure the global logger with options.
+
+    See [Options] for more details.
+ */
+pub fn cfg(opts: &[Options]) -> Result<(), io::Error> {
+    logger().cfg(opts)?;
+    Ok(())
+}
+
+/**
+    Whether the global logger writes messages at `lvl` to any output.
+
+    The macros check this before formatting their message.
+ */
+pub fn enabled(lvl: LogLevel) -> bool {
+    logger().enabled(lvl)
+}
+
+/// Log a message at `lvl` with the global logger.
+pub fn log(lvl: LogLevel, s: &str) {
+    logger().print(lvl, s, &[]);
+}
+
+/**
+    Log a message at `lvl` from `target` with the global logger.
+
+    The macros use this, with the path of the module they are called from as the target.
+ */
+pub fn log_target(lvl: LogLevel, target: &str, s: &str) {
+    logger().print_target(lvl, target, s, &[]);
+}
@@ -0,0 +1,146 @@
+//! Typed values for key/value fields attached to log messages.
+//!
+//! Fields are passed as a slice of `(key, value)` pairs to the `_kv` logging methods, such as
+//! [info_kv](crate::logs::Logger::info_kv). Most primitive types and strings convert into a
+//! [Value] with `.into()`.
+//!
+//! The text format renders fields as `key=value` after the message, quoting strings which
+//! contain whitespace, quotes or `=`. The JSON format keeps them typed, in a `fields` object.
+
+use std::borrow::Cow;
+use std::fmt;
+
+/**
+    The value of a key/value field.
+
+    # Example
+    ```rust
+     use forestry::prelude::*;
+     let fields: [(&str, Value); 3] = [
+         ("status", 200.into()),
+         ("path", "/index.html".into()),
+         ("cached", false.into()),
+     ];
+    ```
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value<'a> {
+    /// A string.
+    Str(Cow<'a, str>),
+    /// A signed integer.
+    Int(i64),
+    /// An unsigned integer.
+    Uint(u64),
+    /// A floating-point number.
+    Float(f64),
+    /// A boolean.
+    Bool(bool),
+}
+
+impl Value<'_> {
+    /// Append the value as it appears after `key=` in the text format.
+    pub(crate) fn push_text(&self, out: &mut String) {
+        match self {
+            Value::Str(s) if s.is_empty() || s.contains(|c: char| c.is_whitespace() || c == '"' || c == '=') => {
+                out.push_str(&format!("{:?}", s));
+            },
+            v => out.push_str(&v.to_string()),
+        }
+    }
+
+    /// Append the value as JSON.
+    pub(crate) fn push_json(&self, out: &mut String) {
+        match self {
+            Value::Str(s) => crate::logs::push_json_str(out, s),
+            Value::Float(f) if !f.is_finite() => out.push_str("null"),
+            v => out.push_str(&v.to_string()),
+        }
+    }
+
+    /// Convert into a value which owns its string, if any.
+    pub fn into_owned(self) -> Value<'static> {
+        match self {
+            Value::Str(s) => Value::Str(Cow::Owned(s.into_owned())),
+            Value::Int(i) => Value::Int(i),
+            Value::Uint(u) => Value::Uint(u),
+            Value::Float(f) => Value::Float(f),
+            Value::Bool(b) => Value::Bool(b),
+        }
+    }
+}
+
+impl fmt::Display for Value<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Str(s) => f.write_str(s),
+            Value::Int(i) => i.fmt(f),
+            Value::Uint(u) => u.fmt(f),
+            Value::Float(x) => x.fmt(f),
+            Value::Bool(b) => b.fmt(f),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Value<'a> {
+    fn from(s: &'a str) -> Self {
+        Value::Str(Cow::Borrowed(s))
+    }
+}
+
+impl From<String> for Value<'_> {
+    fn from(s: String) -> Self {
+        Value::Str(Cow::Owned(s))
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for Value<'a> {
+    fn from(s: Cow<'a, str>) -> Self {
+        Value::Str(s)
+    }
+}
+
+macro_rules! impl_from {
+    ($variant:ident($as:ty): $($t:ty),+) => {
+        $(
+            impl From<$t> for Value<'_> {
+                fn from(v: $t) -> Self {
+                    Value::$variant(v as $as)
+                }
+            }
+        )+
+    };
+}
+
+impl_from!(Int(i64): i8, i16, i32, i64, isize);
+impl_from!(Uint(u64): u8, u16, u32, u64, usize);
+impl_from!(Float(f64): f32, f64);
+
+impl From<bool> for Value<'_> {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+/// Append ` key=value` for each field to `out`.
+pub(crate) fn push_text(out: &mut String, kv: &[(&str, Value<'_>)]) {
+    for (k, v) in kv {
+        out.push(' ');
+        out.push_str(k);
+        out.push('=');
+        v.push_text(out);
+    }
+}
+
+/// Append the fields to `out` as a JSON object.
+pub(crate) fn push_json(out: &mut String, kv: &[(&str, Value<'_>)]) {
+    out.push('{');
+    for (i, (k, v)) in kv.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        crate::logs::push_json_str(out, k);
+        out.push(':');
+        v.push_json(out);
+    }
+    out.push('}');
+}
@@ -0,0 +1,187 @@
+//! A [tracing_subscriber] layer backed by a [Logger].
+//!
+//! Enabled by the `tracing` feature. [ForestryLayer] formats `tracing` events with the same
+//! header and colours as messages logged on a [Logger] directly, so the logger's index, timer
+//! and file options all apply.
+//!
+//! The event's spans are rendered after its message, from outermost to innermost, each with its
+//! own fields in braces. The fields of the event itself are passed on as typed key/value fields
+//! (see the [kv](crate::kv) module), so they are rendered as `key=value` at the end of a text
+//! line and kept structured in JSON output.
+//!
+//! # Example
+//! ```rust
+//!  use forestry::prelude::*;
+//!  use forestry::layer::ForestryLayer;
+//!  use tracing_subscriber::prelude::*;
+//!
+//!  let subscriber = tracing_subscriber::registry().with(ForestryLayer::new(Logger::new()));
+//!  tracing::subscriber::with_default(subscriber, || {
+//!      let span = tracing::info_span!("request", id = 7);
+//!      let _enter = span.enter();
+//!      tracing::info!(status = 200, "done");   // Output: [0000:*] done request{id=7} status=200
+//!  });
+//! ```
+
+use std::fmt::{self, Write};
+use std::sync::Mutex;
+
+use tracing_core::field::{Field, Visit};
+use tracing_core::span::{Attributes, Id, Record};
+use tracing_core::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::kv::Value;
+use crate::logs::{LogLevel, Logger};
+
+impl From<Level> for LogLevel {
+    fn from(lvl: Level) -> Self {
+        match lvl {
+            Level::TRACE => LogLevel::Trace,
+            Level::DEBUG => LogLevel::Debug,
+            Level::INFO => LogLevel::Info,
+            Level::WARN => LogLevel::Warn,
+            Level::ERROR => LogLevel::Error,
+        }
+    }
+}
+
+/**
+    A [Layer] which writes `tracing` events through a [Logger].
+
+    The lock on the logger is taken before an event's fields are visited, so events below
+    its level are discarded without rendering their fields or spans.
+ */
+pub struct ForestryLayer {
+    logger: Mutex<Logger>,
+}
+
+impl ForestryLayer {
+    /// Wrap a configured logger.
+    pub fn new(logger: Logger) -> Self {
+        ForestryLayer {
+            logger: Mutex::new(logger),
+        }
+    }
+}
+
+impl Default for ForestryLayer {
+    fn default() -> Self {
+        Self::new(Logger::new())
+    }
+}
+
+/// The rendered fields of a span, stored in its extensions.
+struct SpanFields(String);
+
+/// Renders the fields of a span as ` key=value` pairs.
+struct SpanVisitor<'a>(&'a mut String);
+
+impl Visit for SpanVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        let _ = write!(self.0, " {}={}", field.name(), value);
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        let _ = write!(self.0, " {}={:?}", field.name(), value);
+    }
+}
+
+/// Collects the fields of an event as typed values, keeping the `message` field separate.
+#[derive(Default)]
+struct EventVisitor {
+    message: String,
+    fields: Vec<(&'static str, Value<'static>)>,
+}
+
+impl EventVisitor {
+    fn push(&mut self, field: &Field, value: Value<'static>) {
+        self.fields.push((field.name(), value));
+    }
+}
+
+impl Visit for EventVisitor {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.push(field, value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.push(field, value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.push(field, value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.push(field, value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            self.push(field, value.to_string().into());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            self.push(field, format!("{:?}", value).into());
+        }
+    }
+}
+
+impl<S> Layer<S> for ForestryLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut fields = String::new();
+        attrs.record(&mut SpanVisitor(&mut fields));
+        span.extensions_mut().insert(SpanFields(fields));
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut ext = span.extensions_mut();
+        if let Some(SpanFields(fields)) = ext.get_mut::<SpanFields>() {
+            values.record(&mut SpanVisitor(fields));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let lvl: LogLevel = (*event.metadata().level()).into();
+        let mut logger = self.logger.lock().unwrap_or_else(|e| e.into_inner());
+        if !logger.enabled(lvl) {
+            return;
+        }
+
+        let mut visitor = EventVisitor::default();
+        event.record(&mut visitor);
+        let mut line = visitor.message;
+
+        if let Some(scope) = ctx.event_scope(event) {
+            let mut sep = ' ';
+            for span in scope.from_root() {
+                line.push(sep);
+                line.push_str(span.name());
+                if let Some(SpanFields(f)) = span.extensions().get::<SpanFields>() {
+                    if !f.is_empty() {
+                        line.push('{');
+                        line.push_str(f.trim_start());
+                        line.push('}');
+                    }
+                }
+                sep = ':';
+            }
+        }
+
+        logger.print_target(lvl, event.metadata().target(), &line, &visitor.fields);
+    }
+}
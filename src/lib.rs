@@ -2,27 +2,99 @@
 //!
 //! Provides a [Logger](logs::Logger) struct that can be used to log messages to the console.
 //! The messages are coloured based on their severity level.
-//! Logs are output with a unique 16-bit log index.
+//! Logs are output with a unique log index, 16-bit by default or optionally 64-bit.
 //! The log also contains a symbol to represent the severity level.
 //!
 //! Most of the time, you will only need to initialize a [Logger](logs::Logger) struct.
 //! After this,you can use the 
+//! - [trace](logs::Logger::trace),
+//! - [debug](logs::Logger::debug),
 //! - [info](logs::Logger::info), 
 //! - [warn](logs::Logger::warn),
 //! - [error](logs::Logger::error), 
 //! - [success](logs::Logger::success), and
 //! - [critical](logs::Logger::critical) methods to log messages.
 //!
-//! Asynchronous versions of the above methods are also available by enabling the `async` feature.
+//! Each of these also has a `_kv` variant, such as [info_kv](logs::Logger::info_kv), which
+//! attaches typed key/value fields to the message; see the [kv] module.
+//!
+//! `trace` and `debug` messages are hidden unless the logger's minimum level is lowered with
+//! [set_level](logs::Logger::set_level).
+//!
+//! The `async` feature adds an `AsyncLogger`, whose logging methods are `async` and write with
+//! `tokio`. It wraps a [Logger](logs::Logger), whose settings and formatting it shares, so the
+//! synchronous API remains available alongside it.
 //!
 //! All configuration can be done using the [cfg()](logs::Logger::cfg) method in conjunction
 //! with the [Options](logs::Options) enumerator. Valid options include adding a timer, logging to
 //! a file, and more.
 //! See the [logs] module for more details.
+//! Alternatively, [Logger::builder()](logs::Logger::builder) returns a
+//! [LoggerBuilder](builder::LoggerBuilder) with a typed setter for each setting, and
+//! [Logger::from_env()](logs::Logger::from_env) reads settings from `FORESTRY_*` environment
+//! variables; see the [env](mod@env) module.
+//! With the `config` feature, `Logger::from_config_file()` reads them from a `forestry.toml`
+//! or JSON file instead (see the `config` module), and the `watch` feature re-applies that file
+//! to a running logger whenever it changes (see the `watch` module).
+//!
+//! Besides stderr and a file, a logger can write to any number of other outputs, each with its
+//! own minimum level and format, by implementing the [Sink](sink::Sink) trait; see the [sink]
+//! module.
+//!
+//! If a line cannot be written, the logger reports it on stderr (the default), ignores it or panics,
+//! as set by its [WriteFailure](logs::WriteFailure) policy; the `try_` methods, such as
+//! [try_info](logs::Logger::try_info), return an [Error] instead. See the [error](mod@error) module.
+//!
+//! The colours and symbols of each level can be changed with a [Theme](theme::Theme); see the
+//! [theme] module.
+//!
+//! Rather than passing a logger around, the [info!], [warn!], [error!], [success!] and
+//! [critical!] macros (plus [trace!] and [debug!]) log `format!`-style messages with a
+//! process-wide logger; see the [global] module.
+//!
+//! To log from several threads through one logger, wrap it in a `SharedLogger` (see the `shared`
+//! module), which can be cloned and sent between threads.
+//! For hot loops, a `NonBlocking` handle (see the `non_blocking` module) queues messages for a
+//! logger on a worker thread, so that logging never waits for output.
+//! `install_panic_hook()` logs every panic through such a logger as a `critical` message, then
+//! flushes it.
+//!
+//! With the `log` feature enabled, the `facade` module provides an implementation of the `log`
+//! crate's facade, so that messages from `log::info!` and friends are written by forestry.
+//! Likewise, the `tracing` feature provides the `layer` module, a `tracing_subscriber` layer
+//! which formats `tracing` events with forestry's headers.
+
+mod macros;
 
 pub mod logs;
+pub mod builder;
+pub mod error;
+pub mod env;
+#[cfg(feature = "config")]
+pub mod config;
+pub mod kv;
+pub mod sink;
+pub mod theme;
+mod pattern;
+pub mod global;
+pub mod shared;
+#[cfg(feature = "watch")]
+pub mod watch;
+pub mod non_blocking;
+mod panic_hook;
+#[cfg(feature = "log")]
+pub mod facade;
+#[cfg(feature = "tracing")]
+pub mod layer;
+pub use crate::error::Error;
+pub use crate::panic_hook::install_panic_hook;
 pub mod prelude {
     pub use crate::logs::*;
+    pub use crate::builder::LoggerBuilder;
+    pub use crate::kv::Value;
+    pub use crate::sink::{Record, Sink, WriteSink};
+    pub use crate::theme::{LevelStyle, Theme};
+    pub use crate::shared::SharedLogger;
 }
 #[cfg(test)]
 mod tests;
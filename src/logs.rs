@@ -1,174 +1,1675 @@
-#[cfg(not(feature = "async"))]
-use std::{fs::File, io::{self, Write}};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Write};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use colored::*;
+#[cfg(feature = "async")]
+use tokio::io::AsyncWriteExt;
+
+use crate::builder::LoggerBuilder;
+use crate::error::Error;
+use crate::kv::{self, Value};
+use crate::sink::{Record, Sink};
+use crate::pattern::{Field, Pattern, Piece};
+use crate::theme::{LevelStyle, Theme};
+
+/// The path of the default log file.
+const DEFAULT_FILE: &str = "forestry.log";
+
+/// The warning logged when the log index wraps around to 0.
+const INDEX_OVERFLOW: &str = "Log index overflowed; log index may be inaccurate.";
+
+/**
+    A simple logger for an application.
+    
+    The logger is used to log messages to the console.
+    The messages are coloured based on their severity level.
+    Logs are output with a unique log index, which is 16-bit unless `WideIndex` is set.
+    Logger also contains an 8-bit options value set by `cfg()`.
+
+    Messages below the minimum [LogLevel] of an output are not written to it;
+    stderr and the file output each have their own threshold.
+ */
+pub struct Logger {
+    index: u64,
+    index_wide: bool,
+    index_width: usize,
+    index_radix: Radix,
+    flags: u8,
+    file: Option<io::BufWriter<File>>,
+    timer: Option<std::time::Instant>,
+    stderr_level: LogLevel,
+    file_level: LogLevel,
+    rotation: RotationState,
+    stderr_format: Format,
+    file_format: Format,
+    clock: Clock,
+    clock_format: Option<String>,
+    sinks: Vec<SinkEntry>,
+    stream: Stream,
+    color: ColorMode,
+    theme: Theme,
+    pattern: Option<Pattern>,
+    /// The header given by the flags, used when no pattern is set; rebuilt when they change.
+    header: Pattern,
+    write_failure: WriteFailure,
+    flush_level: LogLevel,
+    flush_interval: Option<Duration>,
+    last_flush: Instant,
+}
+
+/**
+    A logger which writes to the console and its log file with `tokio`'s asynchronous I/O.
+
+    Requires the `async` feature. An `AsyncLogger` wraps a [Logger] and formats lines in the
+    same way, and has the same getters and the setters which do not touch the log file, such
+    as [set_level](AsyncLogger::set_level) and [add_sink](AsyncLogger::add_sink). Everything
+    else, including opening the log file, goes through its `async` [cfg](AsyncLogger::cfg).
+
+    Sinks are still written to synchronously.
+
+    # Example
+    ```rust
+     use forestry::prelude::*;
+     # #[tokio::main(flavor = "current_thread")] async fn main() {
+     let mut log = AsyncLogger::new();
+     log.set_level(LogLevel::Debug);
+     log.cfg(&[Options::NoSymbol]).await.unwrap();
+     log.debug("debug").await;   // Output: [0000] debug
+     # }
+    ```
+ */
+#[cfg(feature = "async")]
+pub struct AsyncLogger {
+    /// The settings, console state and sinks; its own file is never opened.
+    logger: Logger,
+    file: Option<tokio::io::BufWriter<tokio::fs::File>>,
+}
+
+/// A sink added to a logger, with its own level and format.
+struct SinkEntry {
+    sink: Box<dyn Sink>,
+    level: LogLevel,
+    format: Format,
+}
+
+/// Rotation settings and bookkeeping for the log file.
+struct RotationState {
+    /// The path of the log file, if it was opened by path; files without one are never rotated.
+    path: Option<PathBuf>,
+    /// Whether the file at `path` was opened for appending rather than truncated.
+    append: bool,
+    max_bytes: Option<u64>,
+    every: Rotation,
+    keep: usize,
+    /// Bytes written to the current file.
+    len: u64,
+    /// The rotation period the current file was opened in.
+    period: u64,
+}
+
+impl RotationState {
+    fn new() -> Self {
+        RotationState {
+            path: None,
+            append: false,
+            max_bytes: None,
+            every: Rotation::Never,
+            keep: 5,
+            len: 0,
+            period: 0,
+        }
+    }
+
+    /// Record that a new file of `len` bytes has been opened at `path`.
+    fn opened(&mut self, path: Option<PathBuf>, len: u64) {
+        self.path = path;
+        self.len = len;
+        self.period = self.every.current();
+    }
+
+    /// Whether the file must be rotated before writing `incoming` more bytes to it.
+    fn due(&self, incoming: usize) -> bool {
+        if self.path.is_none() {
+            return false;
+        }
+        let full = self.max_bytes
+            .is_some_and(|max| self.len > 0 && self.len + incoming as u64 > max);
+        full || self.every.current() != self.period
+    }
+
+    /**
+        The renames needed to shift rotated files along by one, in order.
+
+        The oldest rotated file (`path.{keep}`) is overwritten by the last rename.
+     */
+    fn shifts(&self, path: &Path) -> Vec<(PathBuf, PathBuf)> {
+        let mut shifts: Vec<(PathBuf, PathBuf)> = (1..self.keep)
+            .rev()
+            .map(|i| (rotated(path, i), rotated(path, i + 1)))
+            .collect();
+        shifts.push((path.to_path_buf(), rotated(path, 1)));
+        shifts
+    }
+
+    /**
+        The rotated files beyond the `KeepFiles` limit, oldest last.
+
+        Rotated files are numbered without gaps, so callers stop at the first one missing,
+        e.g. after the limit has been lowered.
+     */
+    fn stale(&self, path: &Path) -> impl Iterator<Item = PathBuf> + '_ {
+        let path = path.to_path_buf();
+        (self.keep + 1..).map(move |i| rotated(&path, i))
+    }
+}
+
+/// Apply the foreground and background colours of a level's style.
+fn colorize(mut s: ColoredString, style: &LevelStyle) -> ColoredString {
+    if let Some(fg) = style.fg {
+        s = s.color(fg);
+    }
+    if let Some(bg) = style.bg {
+        s = s.on_color(bg);
+    }
+    s
+}
+
+/**
+    Render `s` with its colours and styles as ANSI escape sequences, or as plain text
+    if `color` is not set.
+
+    This is done here rather than by `colored`, so that whether escape sequences are
+    written depends on the logger's [ColorMode] rather than on global state.
+ */
+fn paint(s: &ColoredString, color: bool) -> String {
+    if !color || s.is_plain() {
+        return s.input.clone();
+    }
+    let mut codes: Vec<std::borrow::Cow<str>> = [
+        (Styles::Bold, "1"),
+        (Styles::Dimmed, "2"),
+        (Styles::Italic, "3"),
+        (Styles::Underline, "4"),
+        (Styles::Blink, "5"),
+        (Styles::Reversed, "7"),
+        (Styles::Hidden, "8"),
+        (Styles::Strikethrough, "9"),
+    ]
+        .into_iter()
+        .filter(|&(style, _)| s.style.contains(style))
+        .map(|(_, code)| code.into())
+        .collect();
+    if let Some(fg) = s.fgcolor {
+        codes.push(fg.to_fg_str());
+    }
+    if let Some(bg) = s.bgcolor {
+        codes.push(bg.to_bg_str());
+    }
+    format!("\x1b[{}m{}\x1b[0m", codes.join(";"), s.input)
+}
+
+/**
+    Whether the environment asks for colour to be forced on or off.
+
+    `CLICOLOR_FORCE` (other than `0`) forces colour on; otherwise, a non-empty `NO_COLOR`
+    or `CLICOLOR=0` turns it off. The environment is read once.
+ */
+fn env_color() -> Option<bool> {
+    static ENV: OnceLock<Option<bool>> = OnceLock::new();
+    *ENV.get_or_init(|| {
+        let var = |k: &str| std::env::var_os(k).filter(|v| !v.is_empty());
+        if var("CLICOLOR_FORCE").is_some_and(|v| v != "0") {
+            Some(true)
+        } else if var("NO_COLOR").is_some() || var("CLICOLOR").is_some_and(|v| v == "0") {
+            Some(false)
+        } else {
+            None
+        }
+    })
+}
+
+/// Append `s` to `out` as a quoted and escaped JSON string.
+pub(crate) fn push_json_str(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Check that `f` is a valid `strftime`-style format string for [Options::TimestampFormat].
+pub(crate) fn check_clock_format(f: &str) -> Result<(), io::Error> {
+    if chrono::format::StrftimeItems::new(f).any(|i| i == chrono::format::Item::Error) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid timestamp format: {:?}", f),
+        ));
+    }
+    Ok(())
+}
+
+/// The path of the `n`th rotated file for `path`; `forestry.log` becomes `forestry.log.n`.
+fn rotated(path: &Path, n: usize) -> PathBuf {
+    let mut s = path.as_os_str().to_owned();
+    s.push(format!(".{n}"));
+    PathBuf::from(s)
+}
+
+impl Default for Logger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Logger {
+    /// Flush the log file and sinks, ignoring any failure.
+    fn drop(&mut self) {
+        let _ = self.flush_all();
+    }
+}
+
+#[cfg(feature = "async")]
+impl Default for AsyncLogger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 #[cfg(feature = "async")]
-use tokio::{fs::File, io::{self, AsyncWriteExt}};
+impl Drop for AsyncLogger {
+    /**
+        Write out the log file's buffer, ignoring any failure; the sinks are flushed when the
+        wrapped [Logger] is dropped.
+
+        The buffer can only be written synchronously if no write to the file is still in
+        flight; otherwise it is lost, so call [shutdown](AsyncLogger::shutdown) first.
+     */
+    fn drop(&mut self) {
+        if let Some(w) = self.file.take() {
+            let buf = w.buffer().to_vec();
+            if let Ok(mut f) = w.into_inner().try_into_std() {
+                let _ = f.write_all(&buf);
+            }
+        }
+    }
+}
+
+impl Logger {
+    /**
+        Create a new logger.
+      
+        The logger is initialised with a log index of 0.
+     */
+    pub fn new() -> Self {
+        Logger {
+            index: 0,
+            index_wide: false,
+            index_width: 4,
+            index_radix: Radix::Hex,
+            flags: 0,
+            file: None,
+            timer: None,
+            stderr_level: LogLevel::Info,
+            file_level: LogLevel::Info,
+            rotation: RotationState::new(),
+            stderr_format: Format::Text,
+            file_format: Format::Text,
+            clock: Clock::Utc,
+            clock_format: None,
+            sinks: Vec::new(),
+            stream: Stream::Stderr,
+            color: ColorMode::Auto,
+            theme: Theme::classic(),
+            pattern: None,
+            header: Pattern::from_flags(0),
+            write_failure: WriteFailure::Stderr,
+            flush_level: LogLevel::Error,
+            flush_interval: None,
+            last_flush: Instant::now(),
+        }
+    }
+
+    /**
+        Start building a logger with typed setters; see [LoggerBuilder].
+     */
+    pub fn builder() -> LoggerBuilder {
+        LoggerBuilder::new()
+    }
+
+    /**
+        Set the minimum level for both stderr and file output.
+
+        Messages below `lvl` are discarded; this may be changed at any time.
+
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         let mut log = Logger::new();
+         log.set_level(LogLevel::Warn);
+        ```
+     */
+    pub fn set_level(&mut self, lvl: LogLevel) -> &mut Self {
+        self.stderr_level = lvl;
+        self.file_level = lvl;
+        self
+    }
+
+    /// Set the minimum level for stderr output.
+    pub fn set_stderr_level(&mut self, lvl: LogLevel) -> &mut Self {
+        self.stderr_level = lvl;
+        self
+    }
+
+    /// Set the minimum level for file output.
+    pub fn set_file_level(&mut self, lvl: LogLevel) -> &mut Self {
+        self.file_level = lvl;
+        self
+    }
+
+    /// Get the minimum level for stderr output.
+    pub fn stderr_level(&self) -> LogLevel {
+        self.stderr_level
+    }
+
+    /// Get the minimum level for file output.
+    pub fn file_level(&self) -> LogLevel {
+        self.file_level
+    }
+
+    /**
+        Add an output to the logger, alongside stderr and the file.
+
+        Messages at or above `lvl` are written to `sink` in the given `format`;
+        text is written without colour or bold. See the [sink](crate::sink) module.
+
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         let mut log = Logger::new();
+         log.add_sink(std::io::stdout(), LogLevel::Trace, Format::Json);
+        ```
+     */
+    pub fn add_sink<S: Sink + 'static>(&mut self, sink: S, lvl: LogLevel, format: Format) -> &mut Self {
+        self.sinks.push(SinkEntry {
+            sink: Box::new(sink),
+            level: lvl,
+            format,
+        });
+        self
+    }
+
+    /// Remove all outputs added with [add_sink](Logger::add_sink).
+    pub fn clear_sinks(&mut self) -> &mut Self {
+        self.sinks.clear();
+        self
+    }
+
+    /**
+        Get the index of the next message.
+
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         let mut log = Logger::new();
+         log.info("info");
+         assert_eq!(log.index(), 1);
+        ```
+     */
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
+    /**
+        Set the index of the next message.
+
+        Unless `WideIndex` is set, the index is 16-bit, so only the low 16 bits of
+        `index` are kept.
+
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         let mut log = Logger::new();
+         log.set_index(0x1000);
+         log.info("info");   // Output: [1000:*] info
+        ```
+     */
+    pub fn set_index(&mut self, index: u64) -> &mut Self {
+        self.index = if self.index_wide { index } else { index as u16 as u64 };
+        self
+    }
+
+    /// Reset the index of the next message to 0, e.g. at the start of a run.
+    pub fn reset_index(&mut self) -> &mut Self {
+        self.index = 0;
+        self
+    }
+
+    /// Whether the log index is shown in the header.
+    pub fn shows_index(&self) -> bool {
+        self.flags & 0b00000001 == 0
+    }
+
+    /// Whether the level symbol is shown in the header.
+    pub fn shows_symbol(&self) -> bool {
+        self.flags & 0b00000010 == 0
+    }
+
+    /// Whether lines are coloured; see also [color_mode](Logger::color_mode).
+    pub fn shows_color(&self) -> bool {
+        self.flags & 0b00000100 == 0
+    }
+
+    /// Whether lines are bold.
+    pub fn shows_bold(&self) -> bool {
+        self.flags & 0b00001000 == 0
+    }
+
+    /// Whether the timer is shown in the header.
+    pub fn shows_timer(&self) -> bool {
+        self.flags & 0b01000000 != 0
+    }
+
+    /// The clock of the wall-clock time shown in the header, if any.
+    pub fn timestamp(&self) -> Option<Clock> {
+        (self.flags & 0b10000000 != 0).then_some(self.clock)
+    }
+
+    /// The `strftime`-style format of the wall-clock time, if not RFC 3339.
+    pub fn timestamp_format(&self) -> Option<&str> {
+        self.clock_format.as_deref()
+    }
+
+    /// The time the timer started at, if any.
+    pub fn timer(&self) -> Option<std::time::Instant> {
+        self.timer
+    }
+
+    /// Whether messages are logged to a file.
+    pub fn logs_to_file(&self) -> bool {
+        self.flags & 0b00010000 != 0
+    }
+
+    /// Whether messages are logged only to the file, and not to the console.
+    pub fn file_only(&self) -> bool {
+        self.flags & 0b00100000 != 0
+    }
+
+    /// The path of the log file, if it was opened by path.
+    pub fn file_path(&self) -> Option<&Path> {
+        self.rotation.path.as_deref()
+    }
+
+    /// Whether the log file at [file_path](Logger::file_path) was opened for appending.
+    #[cfg(feature = "config")]
+    pub(crate) fn file_appends(&self) -> bool {
+        self.rotation.append
+    }
+
+    /// The size the log file is rotated at, if any.
+    pub fn max_file_size(&self) -> Option<u64> {
+        self.rotation.max_bytes
+    }
+
+    /// How often the log file is rotated.
+    pub fn rotation(&self) -> Rotation {
+        self.rotation.every
+    }
+
+    /// The number of rotated log files kept.
+    pub fn keep_files(&self) -> usize {
+        self.rotation.keep
+    }
+
+    /// The console stream(s) written to.
+    pub fn console(&self) -> Stream {
+        self.stream
+    }
+
+    /// The output format for the console.
+    pub fn stderr_format(&self) -> Format {
+        self.stderr_format
+    }
+
+    /// The output format for the file.
+    pub fn file_format(&self) -> Format {
+        self.file_format
+    }
+
+    /// When escape sequences are written to the console.
+    pub fn color_mode(&self) -> ColorMode {
+        self.color
+    }
+
+    /// The colours and symbols of each level.
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// The pattern text lines are laid out with, if any.
+    pub fn pattern(&self) -> Option<&str> {
+        self.pattern.as_ref().map(|p| p.source.as_str())
+    }
+
+    /// What happens when a log line cannot be written.
+    pub fn write_failure(&self) -> WriteFailure {
+        self.write_failure
+    }
+
+    /// The level at or above which every message is followed by a flush.
+    pub fn flush_level(&self) -> LogLevel {
+        self.flush_level
+    }
+
+    /// The longest time buffered output is kept before a message flushes it, if set.
+    pub fn flush_interval(&self) -> Option<Duration> {
+        self.flush_interval
+    }
+
+    /// Whether the log index is 64-bit rather than 16-bit.
+    pub fn wide_index(&self) -> bool {
+        self.index_wide
+    }
+
+    /// The number of digits the log index is padded to.
+    pub fn index_width(&self) -> usize {
+        self.index_width
+    }
+
+    /// The radix the log index is written in.
+    pub fn index_radix(&self) -> Radix {
+        self.index_radix
+    }
+
+    /// Restore every setting to its default, keeping the log index and sinks.
+    fn restore_defaults(&mut self) {
+        let mut fresh = Logger::new();
+        fresh.set_index(self.index);
+        fresh.sinks = std::mem::take(&mut self.sinks);
+        *self = fresh;
+    }
+
+    /// Advance the log index, returning `false` if it wrapped around to 0.
+    fn next_index(&mut self) -> bool {
+        self.index = if self.index_wide {
+            self.index.wrapping_add(1)
+        } else {
+            (self.index as u16).wrapping_add(1) as u64
+        };
+        self.index != 0
+    }
+
+    /// Format the log index with its configured width and radix.
+    fn fmt_index(&self) -> String {
+        match self.index_radix {
+            Radix::Hex => format!("{:0>1$x}", self.index, self.index_width),
+            Radix::Decimal => format!("{:0>1$}", self.index, self.index_width),
+        }
+    }
+
+    /// Whether a message at `lvl` goes to any output.
+    pub(crate) fn enabled(&self, lvl: LogLevel) -> bool {
+        self.to_stderr(lvl) || self.to_file(lvl) || self.sinks.iter().any(|e| lvl >= e.level)
+    }
+
+    /**
+        Write a message to each sink whose level it meets, adding any failed writes to `failed`.
+
+        Each line is formatted at most once per format.
+     */
+    fn write_sinks(&mut self, lvl: LogLevel, target: &str, s: &str, kv: &[(&str, Value<'_>)], failed: &mut Vec<(Error, String)>) {
+        let mut text: Option<String> = None;
+        let mut json: Option<String> = None;
+        let record = Record {
+            index: self.index,
+            level: lvl,
+            target,
+            message: s,
+            fields: kv,
+        };
+        for i in 0..self.sinks.len() {
+            let (level, format) = (self.sinks[i].level, self.sinks[i].format);
+            if lvl < level {
+                continue;
+            }
+            let line = match format {
+                Format::Text => &mut text,
+                Format::Json => &mut json,
+            }
+                .get_or_insert_with(|| self.fmt_line(lvl, target, s, kv, format, false));
+            if let Err(e) = self.sinks[i].sink.write_line(&record, line) {
+                failed.push((Error::Sink(e), line.clone()));
+            }
+        }
+    }
+
+    /// Whether a message at `lvl` must be followed by a flush.
+    fn flush_due(&self, lvl: LogLevel) -> bool {
+        lvl >= self.flush_level || self.flush_interval.is_some_and(|d| self.last_flush.elapsed() >= d)
+    }
+
+    /// Flush each sink, adding any failures to `failed`.
+    fn flush_sinks(&mut self, failed: &mut Vec<Error>) {
+        for entry in &mut self.sinks {
+            if let Err(e) = entry.sink.flush_sink() {
+                failed.push(Error::Sink(e));
+            }
+        }
+        self.last_flush = Instant::now();
+    }
+
+    /// Handle a failed write of `line` according to the logger's [WriteFailure] policy.
+    fn write_failed(&self, e: Error, line: &str) {
+        match self.write_failure {
+            WriteFailure::Ignore => {},
+            WriteFailure::Stderr => {
+                let msg = format!("forestry: {}\n{}", e, line);
+                let _ = std::io::Write::write_all(&mut std::io::stderr(), msg.as_bytes());
+            },
+            WriteFailure::Panic => panic!("forestry: {}", e),
+        }
+    }
+
+    /**
+        Apply an option which does not open, close or reset the log file.
+
+        Both [Logger::cfg] and `AsyncLogger::cfg` handle the file options themselves, with
+        their own I/O, and pass every other option here.
+     */
+    fn apply_option(&mut self, opt: Options) -> Result<(), io::Error> {
+        let flags = self.flags;
+        match opt {
+            Options::NoIndex =>   self.flags |= 0b00000001,
+            Options::NoSymbol =>  self.flags |= 0b00000010,
+            Options::NoColor =>   self.flags |= 0b00000100,
+            Options::NoBold =>    self.flags |= 0b00001000,
+            Options::Plain =>     self.flags |= 0b00001100,
+            Options::Basic =>     self.flags |= 0b00001111,
+            Options::Index =>     self.flags &= !0b00000001,
+            Options::Symbol =>    self.flags &= !0b00000010,
+            Options::Color =>     self.flags &= !0b00000100,
+            Options::Bold =>      self.flags &= !0b00001000,
+            Options::FileOnly =>  self.flags |= 0b00100000,
+            Options::Timer => {
+                self.flags |= 0b01000000;
+                self.timer = Some(std::time::Instant::now());
+            },
+            Options::TimerAt(t) => {
+                self.flags |= 0b01000000;
+                self.timer = Some(*t);
+            },
+            Options::NoTimer => {
+                self.flags &= !0b01000000;
+                self.timer = None;
+            },
+            Options::Level(l) =>  { self.set_level(l); },
+            Options::StderrLevel(l) => { self.set_stderr_level(l); },
+            Options::FileLevel(l) => { self.set_file_level(l); },
+            Options::MaxFileSize(n) => self.rotation.max_bytes = Some(n),
+            Options::Rotate(r) => {
+                self.rotation.every = r;
+                self.rotation.period = r.current();
+            },
+            Options::KeepFiles(n) => self.rotation.keep = n,
+            Options::NoRotation => {
+                self.rotation.max_bytes = None;
+                self.rotation.every = Rotation::Never;
+            },
+            Options::StderrFormat(f) => self.stderr_format = f,
+            Options::Console(c) => {
+                self.flags &= !0b00100000;
+                self.stream = c;
+            },
+            Options::ColorAuto => self.color = ColorMode::Auto,
+            Options::ColorAlways => self.color = ColorMode::Always,
+            Options::ColorNever => self.color = ColorMode::Never,
+            Options::UseTheme(t) => self.theme = t.clone(),
+            Options::WideIndex => self.index_wide = true,
+            Options::NarrowIndex => {
+                self.index_wide = false;
+                self.index = self.index as u16 as u64;
+            },
+            Options::IndexWidth(w) => self.index_width = w,
+            Options::IndexRadix(r) => self.index_radix = r,
+            Options::Pattern(p) => self.pattern = Some(Pattern::parse(p)?),
+            Options::NoPattern => self.pattern = None,
+            Options::OnWriteFailure(w) => self.write_failure = w,
+            Options::FlushLevel(l) => self.flush_level = l,
+            Options::FlushInterval(d) => self.flush_interval = Some(d),
+            Options::NoFlushInterval => self.flush_interval = None,
+            Options::FileFormat(f) => self.file_format = f,
+            Options::Timestamp(c) => {
+                self.flags |= 0b10000000;
+                self.clock = c;
+                self.clock_format = None;
+            },
+            Options::TimestampFormat(f) => {
+                check_clock_format(f)?;
+                self.flags |= 0b10000000;
+                self.clock_format = Some(f.to_string());
+            },
+            Options::NoTimestamp => self.flags &= !0b10000000,
+            Options::File
+                | Options::FileAt(_)
+                | Options::FilePath(_)
+                | Options::FileAppend(_)
+                | Options::NoFile
+                | Options::Reset => {},
+        }
+        if self.flags != flags {
+            self.header = Pattern::from_flags(self.flags);
+        }
+        Ok(())
+    }
+
+    /// Record that a log file has been opened, at `path` if it has one, with `len` bytes.
+    fn file_opened(&mut self, path: Option<PathBuf>, len: u64) {
+        self.flags |= 0b00010000;
+        self.rotation.opened(path, len);
+    }
+
+    /// Record that the log file has been closed, which also undoes `FileOnly`.
+    fn file_closed(&mut self) {
+        self.flags &= !0b00110000;
+        self.rotation.opened(None, 0);
+    }
+
+    /// The line written to the console for a message at `lvl`, if any, and whether it goes to stdout.
+    fn console_line(&self, lvl: LogLevel, target: &str, s: &str, kv: &[(&str, Value<'_>)]) -> Option<(String, bool)> {
+        if !self.to_stderr(lvl) {
+            return None;
+        }
+        let stdout = self.stream.is_stdout(lvl);
+        let tty = if stdout {
+            std::io::stdout().is_terminal()
+        } else {
+            std::io::stderr().is_terminal()
+        };
+        Some((self.fmt_line(lvl, target, s, kv, self.stderr_format, self.color.enabled(tty)), stdout))
+    }
+
+    /// The line written to the log file for a message at `lvl`, if any.
+    fn file_line(&self, lvl: LogLevel, target: &str, s: &str, kv: &[(&str, Value<'_>)]) -> Option<String> {
+        self.to_file(lvl).then(|| self.fmt_line(lvl, target, s, kv, self.file_format, false))
+    }
+
+    /// Whether a message at `lvl` goes to stderr.
+    fn to_stderr(&self, lvl: LogLevel) -> bool {
+        self.flags & 0b00100000 == 0 && lvl >= self.stderr_level
+    }
+
+    /// Whether a message at `lvl` goes to the file.
+    fn to_file(&self, lvl: LogLevel) -> bool {
+        self.flags & 0b00010000 != 0 && lvl >= self.file_level
+    }
+
+    /**
+        Format a complete line for output, including the trailing newline.
+
+        Text lines contain escape sequences only if `color` is set.
+     */
+    fn fmt_line(&self, lvl: LogLevel, target: &str, s: &str, kv: &[(&str, Value<'_>)], format: Format, color: bool) -> String {
+        if format == Format::Json {
+            return self.fmt_json(lvl, target, s, kv);
+        }
+        let mut line = self.fmt_text(lvl, target, s, kv, color);
+        line.push('\n');
+        line
+    }
+
+    /**
+        Format a line as a JSON object, including the trailing newline.
+
+        The object always contains every field, regardless of formatting options;
+        `elapsed_ms` is `null` unless a timer is enabled, `target` is present only if the
+        message was logged from a known target, and `fields` holds any key/value fields.
+     */
+    fn fmt_json(&self, lvl: LogLevel, target: &str, s: &str, kv: &[(&str, Value<'_>)]) -> String {
+        let mut line = format!("{{\"index\":{},\"level\":\"{}\",\"message\":", self.index, lvl.as_str());
+        push_json_str(&mut line, s);
+        line.push_str(",\"elapsed_ms\":");
+        match self.timer {
+            Some(t) if self.flags & 0b01000000 != 0 => {
+                let micros = t.elapsed().as_micros();
+                line.push_str(&format!("{:.3}", micros as f64 / 1_000.0));
+            },
+            _ => line.push_str("null"),
+        }
+        line.push_str(",\"timestamp\":\"");
+        let now = chrono::Utc::now();
+        line.push_str(&match self.clock {
+            Clock::Utc => now.to_rfc3339_opts(chrono::SecondsFormat::Micros, true),
+            Clock::Local => now.with_timezone(&chrono::Local).to_rfc3339_opts(chrono::SecondsFormat::Micros, false),
+        });
+        line.push('"');
+        if !target.is_empty() {
+            line.push_str(",\"target\":");
+            push_json_str(&mut line, target);
+        }
+        line.push_str(",\"fields\":");
+        kv::push_json(&mut line, kv);
+        line.push_str("}\n");
+        line
+    }
+
+    /**
+        Format the current wall-clock time, with `format` if given or else the
+        logger's timestamp format.
+     */
+    fn fmt_clock(&self, format: Option<&str>) -> String {
+        let now = chrono::Utc::now();
+        match (self.clock, format.or(self.clock_format.as_deref())) {
+            (Clock::Utc, None) => now.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            (Clock::Utc, Some(f)) => now.format(f).to_string(),
+            (Clock::Local, None) => now
+                .with_timezone(&chrono::Local)
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+            (Clock::Local, Some(f)) => now.with_timezone(&chrono::Local).format(f).to_string(),
+        }
+    }
+
+    /**
+        Format a text line, without the trailing newline, by filling in the logger's
+        pattern, or the pattern given by its flags if none is set.
+
+        Header placeholders take the level's colours and are bold; the message and
+        its fields are styled by [fmt_string](Logger::fmt_string).
+     */
+    fn fmt_text(&self, lvl: LogLevel, target: &str, s: &str, kv: &[(&str, Value<'_>)], color: bool) -> String {
+        let pattern = self.pattern.as_ref().unwrap_or(&self.header);
+        let style = self.theme.get(lvl);
+        let mut line = String::new();
+        for piece in &pattern.pieces {
+            let (field, spec) = match piece {
+                Piece::Literal(s) => {
+                    line.push_str(s);
+                    continue;
+                },
+                Piece::Field(field, spec) => (field, spec),
+            };
+            let part = match field {
+                Field::Index => spec.index(self.index, self.fmt_index()),
+                Field::Level => spec.text(&lvl.as_str().to_uppercase()),
+                Field::Symbol => spec.text(&style.symbol),
+                Field::Time(f) => self.fmt_clock(f.as_deref()),
+                Field::Elapsed => match self.timer {
+                    Some(t) => {
+                        let msecs = t.elapsed().as_micros() as f64 / 1_000.0;
+                        spec.pad(&format!("{:.*}ms", spec.precision.unwrap_or(3), msecs), true)
+                    },
+                    None => spec.pad("", true),
+                },
+                Field::Target => spec.text(target),
+                Field::Msg => {
+                    let mut msg = s.to_string();
+                    if !pattern.fields {
+                        kv::push_text(&mut msg, kv);
+                    }
+                    line.push_str(&self.fmt_string(lvl, &spec.text(&msg), color));
+                    continue;
+                },
+                Field::Fields => {
+                    let mut fields = String::new();
+                    kv::push_text(&mut fields, kv);
+                    line.push_str(&self.fmt_string(lvl, &spec.text(fields.trim_start()), color));
+                    continue;
+                },
+            };
+            let mut part: ColoredString = part.into();
+            if self.flags & 0b0100 == 0 {
+                part = colorize(part, style);
+            }
+            if self.flags & 0b1000 == 0 {
+                part = part.bold();
+            }
+            line.push_str(&paint(&part, color));
+        }
+        line
+    }
+
+    fn fmt_string(&self, lvl: LogLevel, s: &str, color: bool) -> String {
+        let style = self.theme.get(lvl);
+        let mut fmt: ColoredString = s.into();
+        if self.flags & 0b0100 == 0 {
+            fmt = colorize(fmt, style);
+        }
+        if self.flags & 0b1000 == 0 {
+            if style.bold {
+                fmt = fmt.bold();
+            }
+            if style.italic {
+                fmt = fmt.italic();
+            }
+            if style.underline {
+                fmt = fmt.underline();
+            }
+        }
+        paint(&fmt, color)
+    }
+}
+
+impl Logger {
+    /**
+        Configure the logger with options.
+        
+        See [Options] for more details.
+        
+        # Arguments
+        - `opts`: an array of [Options]
+     */
+    pub fn cfg(&mut self, opts: &[Options]) -> Result<&mut Self, io::Error> {
+        for &e in opts {
+            match e {
+                Options::File => self.open(Path::new(DEFAULT_FILE), false)?,
+                Options::FileAt(f) => {
+                    self.file = Some(io::BufWriter::new(f.try_clone()?));
+                    self.file_opened(None, 0);
+                },
+                Options::FilePath(p) => self.open(p, false)?,
+                Options::FileAppend(p) => self.open(p, true)?,
+                Options::NoFile => self.close()?,
+                Options::Reset => self.reset()?,
+                e => self.apply_option(e)?,
+            }
+        }
+        Ok(self)
+    }
+
+    /**
+        Log a message.
+        
+        The message is logged as a TRACE message.
+        It is hidden unless the minimum level is `Trace`.
+        
+        # Arguments
+        - `s`: The message to log.
+        
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         let mut log = Logger::new();
+         log.set_level(LogLevel::Trace);
+         log.trace("trace");           // Output: [0000:.] trace
+        ```
+     */
+    pub fn trace(&mut self, s: &str) -> &mut Self {
+        self.print(LogLevel::Trace, s, &[])
+    }
+
+    /**
+        Log a message.
+        
+        The message is logged as a DEBUG message.
+        It is hidden unless the minimum level is `Debug` or lower.
+        
+        # Arguments
+        - `s`: The message to log.
+        
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         let mut log = Logger::new();
+         log.set_level(LogLevel::Debug);
+         log.debug("debug");           // Output: [0000:?] debug
+        ```
+     */
+    pub fn debug(&mut self, s: &str) -> &mut Self {
+        self.print(LogLevel::Debug, s, &[])
+    }
+
+    /**
+        Log a message.
+        
+        The message is logged as an INFO message.
+        
+        # Arguments
+        - `s`: The message to log.
+        
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         let mut log = Logger::new();
+         log.info("info");            // Output: [0000:*] info
+        ```
+     */
+    pub fn info(&mut self, s: &str) -> &mut Self {
+        self.print(LogLevel::Info, s, &[])
+    }
+
+    /**
+        Log a message.
+        
+        The message is logged as a WARN message.
+        
+        # Arguments
+        - `s`: The message to log.
+        
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         let mut log = Logger::new();
+         log.warn("warn");            // Output: [0000:~] warn
+        ```
+     */
+    pub fn warn(&mut self, s: &str) -> &mut Self {
+        self.print(LogLevel::Warn, s, &[])
+    }
+
+    /**
+        Log a message.
+        
+        The message is logged as an ERROR message.
+        
+        # Arguments
+        - `s`: The message to log.
+        
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         let mut log = Logger::new();
+         log.error("error");           // Output: [0000:!] error
+        ```
+     */
+    pub fn error(&mut self, s: &str) -> &mut Self {
+        self.print(LogLevel::Error, s, &[])
+    }
+
+    /**
+        Log a message.
+        
+        The message is logged as a SUCCESS message.
+        
+        # Arguments
+        - `s`: The message to log.
+        
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         let mut log = Logger::new();
+         log.success("success");         // Output: [0000:+] success
+        ```
+     */
+    pub fn success(&mut self, s: &str) -> &mut Self {
+        self.print(LogLevel::Success, s, &[])
+    }
+
+    /**
+        Log a message,
+
+        The message is logged as a CRITICAL message.
+
+        # Arguments
+        - `s`: The message to log.
+
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         let mut log = Logger::new();
+         log.critical("critical");        // Output: [0000:%] critical
+        ```
+    */
+    pub fn critical(&mut self, s: &str) -> &mut Self {
+        self.print(LogLevel::Critical, s, &[])
+    }
+
+    /**
+        Log a message at the given level.
+        
+        # Arguments
+        - `lvl`: The level of the message.
+        - `s`: The message to log.
+     */
+    pub fn log(&mut self, lvl: LogLevel, s: &str) -> &mut Self {
+        self.print(lvl, s, &[])
+    }
+
+    /**
+        Log a message with key/value fields at the given level.
+        
+        # Arguments
+        - `lvl`: The level of the message.
+        - `s`: The message to log.
+        - `kv`: The fields to attach to the message.
+     */
+    pub fn log_kv(&mut self, lvl: LogLevel, s: &str, kv: &[(&str, Value<'_>)]) -> &mut Self {
+        self.print(lvl, s, kv)
+    }
+
+    /**
+        Log a message at the given level, or return the first write which failed.
+
+        Unlike [log](Logger::log), a failed write is returned rather than handled by the
+        [WriteFailure] policy. The other outputs are still written to, and the log index
+        still advances.
+
+        # Arguments
+        - `lvl`: The level of the message.
+        - `s`: The message to log.
+
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         let mut log = Logger::new();
+         log.try_log(LogLevel::Info, "info").unwrap();
+        ```
+     */
+    pub fn try_log(&mut self, lvl: LogLevel, s: &str) -> Result<&mut Self, Error> {
+        self.try_print_target(lvl, "", s, &[])
+    }
+
+    /// Log a message with key/value fields at the given level, or return the first write which failed; see [try_log](Logger::try_log).
+    pub fn try_log_kv(&mut self, lvl: LogLevel, s: &str, kv: &[(&str, Value<'_>)]) -> Result<&mut Self, Error> {
+        self.try_print_target(lvl, "", s, kv)
+    }
+
+    /// Log a TRACE message, or return the first write which failed; see [try_log](Logger::try_log).
+    pub fn try_trace(&mut self, s: &str) -> Result<&mut Self, Error> {
+        self.try_print_target(LogLevel::Trace, "", s, &[])
+    }
+
+    /// Log a DEBUG message, or return the first write which failed; see [try_log](Logger::try_log).
+    pub fn try_debug(&mut self, s: &str) -> Result<&mut Self, Error> {
+        self.try_print_target(LogLevel::Debug, "", s, &[])
+    }
+
+    /// Log an INFO message, or return the first write which failed; see [try_log](Logger::try_log).
+    pub fn try_info(&mut self, s: &str) -> Result<&mut Self, Error> {
+        self.try_print_target(LogLevel::Info, "", s, &[])
+    }
+
+    /// Log a SUCCESS message, or return the first write which failed; see [try_log](Logger::try_log).
+    pub fn try_success(&mut self, s: &str) -> Result<&mut Self, Error> {
+        self.try_print_target(LogLevel::Success, "", s, &[])
+    }
+
+    /// Log a WARN message, or return the first write which failed; see [try_log](Logger::try_log).
+    pub fn try_warn(&mut self, s: &str) -> Result<&mut Self, Error> {
+        self.try_print_target(LogLevel::Warn, "", s, &[])
+    }
+
+    /// Log an ERROR message, or return the first write which failed; see [try_log](Logger::try_log).
+    pub fn try_error(&mut self, s: &str) -> Result<&mut Self, Error> {
+        self.try_print_target(LogLevel::Error, "", s, &[])
+    }
+
+    /// Log a CRITICAL message, or return the first write which failed; see [try_log](Logger::try_log).
+    pub fn try_critical(&mut self, s: &str) -> Result<&mut Self, Error> {
+        self.try_print_target(LogLevel::Critical, "", s, &[])
+    }
+
+    /**
+        Log a message with key/value fields.
+        
+        The message is logged as a TRACE message, followed by its fields.
+        
+        # Arguments
+        - `s`: The message to log.
+        - `kv`: The fields to attach to the message.
+        
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         let mut log = Logger::new();
+         log.set_level(LogLevel::Trace);
+         log.trace_kv("trace", &[("status", 200.into())]);   // Output: [0000:.] trace status=200
+        ```
+     */
+    pub fn trace_kv(&mut self, s: &str, kv: &[(&str, Value<'_>)]) -> &mut Self {
+        self.print(LogLevel::Trace, s, kv)
+    }
+
+    /**
+        Log a message with key/value fields.
+        
+        The message is logged as a DEBUG message, followed by its fields.
+        
+        # Arguments
+        - `s`: The message to log.
+        - `kv`: The fields to attach to the message.
+        
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         let mut log = Logger::new();
+         log.set_level(LogLevel::Debug);
+         log.debug_kv("debug", &[("status", 200.into())]);   // Output: [0000:?] debug status=200
+        ```
+     */
+    pub fn debug_kv(&mut self, s: &str, kv: &[(&str, Value<'_>)]) -> &mut Self {
+        self.print(LogLevel::Debug, s, kv)
+    }
+
+    /**
+        Log a message with key/value fields.
+        
+        The message is logged as an INFO message, followed by its fields.
+        
+        # Arguments
+        - `s`: The message to log.
+        - `kv`: The fields to attach to the message.
+        
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         let mut log = Logger::new();
+         log.info_kv("info", &[("status", 200.into())]);   // Output: [0000:*] info status=200
+        ```
+     */
+    pub fn info_kv(&mut self, s: &str, kv: &[(&str, Value<'_>)]) -> &mut Self {
+        self.print(LogLevel::Info, s, kv)
+    }
+
+    /**
+        Log a message with key/value fields.
+        
+        The message is logged as a WARN message, followed by its fields.
+        
+        # Arguments
+        - `s`: The message to log.
+        - `kv`: The fields to attach to the message.
+        
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         let mut log = Logger::new();
+         log.warn_kv("warn", &[("status", 200.into())]);   // Output: [0000:~] warn status=200
+        ```
+     */
+    pub fn warn_kv(&mut self, s: &str, kv: &[(&str, Value<'_>)]) -> &mut Self {
+        self.print(LogLevel::Warn, s, kv)
+    }
+
+    /**
+        Log a message with key/value fields.
+        
+        The message is logged as an ERROR message, followed by its fields.
+        
+        # Arguments
+        - `s`: The message to log.
+        - `kv`: The fields to attach to the message.
+        
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         let mut log = Logger::new();
+         log.error_kv("error", &[("status", 200.into())]);   // Output: [0000:!] error status=200
+        ```
+     */
+    pub fn error_kv(&mut self, s: &str, kv: &[(&str, Value<'_>)]) -> &mut Self {
+        self.print(LogLevel::Error, s, kv)
+    }
+
+    /**
+        Log a message with key/value fields.
+        
+        The message is logged as a SUCCESS message, followed by its fields.
+        
+        # Arguments
+        - `s`: The message to log.
+        - `kv`: The fields to attach to the message.
+        
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         let mut log = Logger::new();
+         log.success_kv("success", &[("status", 200.into())]);   // Output: [0000:+] success status=200
+        ```
+     */
+    pub fn success_kv(&mut self, s: &str, kv: &[(&str, Value<'_>)]) -> &mut Self {
+        self.print(LogLevel::Success, s, kv)
+    }
+
+    /**
+        Log a message with key/value fields.
+        
+        The message is logged as a CRITICAL message, followed by its fields.
+        
+        # Arguments
+        - `s`: The message to log.
+        - `kv`: The fields to attach to the message.
+        
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         let mut log = Logger::new();
+         log.critical_kv("critical", &[("status", 200.into())]);   // Output: [0000:%] critical status=200
+        ```
+     */
+    pub fn critical_kv(&mut self, s: &str, kv: &[(&str, Value<'_>)]) -> &mut Self {
+        self.print(LogLevel::Critical, s, kv)
+    }
 
-use colored::*;
+    /**
+        Write out any buffered output to the log file and sinks.
 
-/**
-    A simple logger for an application.
-    
-    The logger is used to log messages to the console.
-    The messages are coloured based on their severity level.
-    Logs are output with a unique 16-bit log index.
-    Logger also contains an 8-bit options value set by `cfg()`.
- */
-pub struct Logger {
-    index: u16,
-    flags: u8,
-    file: Option<io::BufWriter<File>>,
-    timer: Option<std::time::Instant>,
-}
+        The file is buffered, so lines may not reach it until it is flushed. This happens
+        automatically after messages at or above the flush level (`Error` by default; see
+        [Options::FlushLevel]), when the logger is dropped, and optionally at an interval
+        (see [Options::FlushInterval]); call this before `std::process::exit`, which skips
+        destructors.
 
-impl Logger {
-    /**
-        Create a new logger.
-      
-        The logger is initialised with a log index of 0.
+        Every output is flushed even if one fails; the error is that of the first which failed.
+
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         let mut log = Logger::new();
+         log.info("info").flush().unwrap();
+        ```
      */
-    pub fn new() -> Self {
-        Logger {
-            index: 0,
-            flags: 0,
-            file: None,
-            timer: None,
+    pub fn flush(&mut self) -> Result<&mut Self, Error> {
+        match self.flush_all().into_iter().next() {
+            Some(e) => Err(e),
+            None => Ok(self),
         }
     }
 
-    fn fmt_header(&self, lvl: LogLevel) -> String {
-        // If no part of the header is desired, return a blank string.
-        if self.flags & 0b01000011 == 0b00000011 {
-            return "".to_string();
+    /// Flush the file and every sink, returning the failures.
+    fn flush_all(&mut self) -> Vec<Error> {
+        let mut failed = Vec::new();
+        if let Some(Err(e)) = self.file.as_mut().map(|f| f.flush()) {
+            failed.push(Error::File(e));
         }
-        let mut cnt: ColoredString = "".into();
-        let mut sym: ColoredString = "".into();
-        let mut tim: ColoredString = "".into();
+        self.flush_sinks(&mut failed);
+        failed
+    }
 
-        if self.flags & 0b0001 == 0 {
-            cnt = format!("{:0>4x}", self.index).into();
+    /// Flush and close the log file, and stop logging to it.
+    fn close(&mut self) -> Result<(), io::Error> {
+        self.file_closed();
+        if let Some(mut f) = self.file.take() {
+            f.flush()?;
         }
-        if self.flags & 0b0010 == 0 {
-            sym = match lvl {
-                LogLevel::Info => "*".into(),
-                LogLevel::Warn => "~".into(),
-                LogLevel::Error => "!".into(),
-                LogLevel::Success => "+".into(),
-                LogLevel::Critical => "%".into(),
-            };
+        Ok(())
+    }
+
+    /// Close the log file and restore every setting to its default.
+    fn reset(&mut self) -> Result<(), io::Error> {
+        self.close()?;
+        self.restore_defaults();
+        Ok(())
+    }
+
+    /**
+        Open the log file at `path`, creating any missing parent directories.
+
+        The current log file is flushed and dropped first. The new file is appended to if
+        `append` is set, and truncated otherwise.
+     */
+    fn open(&mut self, path: &Path, append: bool) -> Result<(), io::Error> {
+        if let Some(mut f) = self.file.take() {
+            f.flush()?;
         }
-        if self.flags & 0b01000000 != 0 {
-            let micros = self.timer.unwrap().elapsed().as_micros();
-            let msecs = micros as f64 / 1_000.0;
-            tim = format!("{:.3}ms", msecs).into();
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
         }
-        if self.flags & 0b0100 == 0 {
-            match lvl {
-                LogLevel::Info => {
-                    cnt = cnt.blue();
-                    sym = sym.blue();
-                    tim = tim.blue();
-                },
-                LogLevel::Warn => {
-                    cnt = cnt.yellow();
-                    sym = sym.yellow();
-                    tim = tim.yellow();
-                },
-                LogLevel::Error => {
-                    cnt = cnt.red();
-                    sym = sym.red();
-                    tim = tim.red();
-                },
-                LogLevel::Success => {
-                    cnt = cnt.green();
-                    sym = sym.green();
-                    tim = tim.green();
-                },
-                LogLevel::Critical => {
-                    cnt = cnt.on_red().white();
-                    sym = sym.on_red().white();
-                    tim = tim.on_red().white();
-                },
+        let f = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .append(append)
+            .truncate(!append)
+            .open(path)?;
+        let len = f.metadata()?.len();
+        self.file = Some(io::BufWriter::new(f));
+        self.file_opened(Some(path.to_path_buf()), len);
+        self.rotation.append = append;
+        Ok(())
+    }
+
+    /**
+        Move the log file aside and start a new one.
+
+        `forestry.log` becomes `forestry.log.1`, `forestry.log.1` becomes
+        `forestry.log.2`, and so on; files beyond the `KeepFiles` limit, including any
+        left from a higher limit, are removed.
+     */
+    fn rotate(&mut self) -> Result<(), io::Error> {
+        let Some(path) = self.rotation.path.clone() else { return Ok(()) };
+        if let Some(mut f) = self.file.take() {
+            f.flush()?;
+        }
+        if self.rotation.keep > 0 {
+            for (from, to) in self.rotation.shifts(&path) {
+                match std::fs::rename(&from, &to) {
+                    Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                    _ => {},
+                }
             }
         }
-        if self.flags & 0b1000 == 0 {
-            cnt = cnt.bold();
-            sym = sym.bold();
-            tim = tim.bold();
-        }
-        #[allow(unused_assignments)]
-        let mut res = String::from("");
-        if self.flags & 0b0011 == 0 {
-            let cnt: String = cnt.to_string();
-            let sym: String = sym.to_string();
-            res.push('[');
-            res.push_str(&cnt);
-            res.push(':');
-            res.push_str(&sym);
-            res.push(']');
-        } else if self.flags & 0b0001 == 0 {
-            let cnt: String = cnt.to_string();
-            res.push('[');
-            res.push_str(&cnt);
-            res.push(']');
-        } else {
-            let sym: String = sym.to_string();
-            res.push('[');
-            res.push_str(&sym);
-            res.push(']');
+        for stale in self.rotation.stale(&path) {
+            match std::fs::remove_file(&stale) {
+                Err(e) if e.kind() == io::ErrorKind::NotFound => break,
+                r => r?,
+            }
         }
-        if self.flags & 0b01000000 != 0 {
-            let tim: String = tim.to_string();
-            res.push('(');
-            res.push_str(&tim);
-            res.push(')');
+        self.file = Some(io::BufWriter::new(File::create(&path)?));
+        self.rotation.opened(Some(path), 0);
+        Ok(())
+    }
+
+    pub(crate) fn print(&mut self, lvl: LogLevel, string: &str, kv: &[(&str, Value<'_>)]) -> &mut Self {
+        self.print_target(lvl, "", string, kv)
+    }
+
+    /// Log a message from the given target, such as a module path.
+    pub(crate) fn print_target(&mut self, lvl: LogLevel, target: &str, string: &str, kv: &[(&str, Value<'_>)]) -> &mut Self {
+        for (e, line) in self.emit(lvl, target, string, kv) {
+            self.write_failed(e, &line);
         }
-        res.push(' ');
-        res
+        self
     }
 
-    fn fmt_string(&self, lvl: LogLevel, s: &str) -> String {
-        let mut fmt: ColoredString = s.into();
-        if self.flags & 0b0100 == 0 {
-            match lvl {
-                LogLevel::Info => {
-                    fmt = fmt.blue()
-                },
-                LogLevel::Warn => {
-                    fmt = fmt.yellow()
-                },
-                LogLevel::Error => {
-                    fmt = fmt.red()
-                },
-                LogLevel::Success => {
-                    fmt = fmt.green()
-                },
-                LogLevel::Critical => {
-                    fmt = fmt.on_red().white()
-                },
+    /// Log a message from the given target, returning the first write which failed.
+    pub(crate) fn try_print_target(&mut self, lvl: LogLevel, target: &str, string: &str, kv: &[(&str, Value<'_>)]) -> Result<&mut Self, Error> {
+        match self.emit(lvl, target, string, kv).into_iter().next() {
+            Some((e, _)) => Err(e),
+            None => Ok(self),
+        }
+    }
+
+    /**
+        Write a message to every output it goes to, and advance the log index.
+
+        A failed write does not stop the others; the failures are returned along
+        with the lines which were not written.
+     */
+    fn emit(&mut self, lvl: LogLevel, target: &str, string: &str, kv: &[(&str, Value<'_>)]) -> Vec<(Error, String)> {
+        if !self.enabled(lvl) {
+            return Vec::new();
+        }
+        let mut failed = self.write_outputs(lvl, target, string, kv);
+        if !self.next_index() {
+            failed.extend(self.write_outputs(LogLevel::Warn, "", INDEX_OVERFLOW, &[]));
+            self.next_index();
+        }
+        failed
+    }
+
+    /// Write a message to every output it goes to, and flush them if due, without advancing the log index.
+    fn write_outputs(&mut self, lvl: LogLevel, target: &str, string: &str, kv: &[(&str, Value<'_>)]) -> Vec<(Error, String)> {
+        let mut failed = Vec::new();
+        if let Some((s, stdout)) = self.console_line(lvl, target, string, kv) {
+            let written = if stdout {
+                io::stdout().write_all(s.as_bytes())
+            } else {
+                io::stderr().write_all(s.as_bytes())
+            };
+            if let Err(e) = written {
+                failed.push((Error::Console(e), s));
             }
         }
-        if self.flags & 0b1000 == 0 {
-            match lvl {
-                LogLevel::Info => {},
-                LogLevel::Warn => {},
-                LogLevel::Error => {
-                    fmt = fmt.bold()
-                },
-                LogLevel::Success => {
-                    fmt = fmt.bold()
-                },
-                LogLevel::Critical => {
-                    fmt = fmt.bold()
-                },
+        if let Some(plain) = self.file_line(lvl, target, string, kv) {
+            if let Err(e) = self.write_file(&plain) {
+                failed.push((Error::File(e), plain));
             }
         }
-        fmt.to_string()
+        self.write_sinks(lvl, target, string, kv, &mut failed);
+        if self.flush_due(lvl) {
+            failed.extend(self.flush_all().into_iter().map(|e| (e, String::new())));
+        }
+        failed
+    }
+
+    /// Write a line to the log file, rotating it first if it is due.
+    fn write_file(&mut self, line: &str) -> Result<(), io::Error> {
+        if self.rotation.due(line.len()) {
+            self.rotate()?;
+        }
+        let Some(f) = self.file.as_mut() else {
+            return Err(io::Error::new(io::ErrorKind::NotFound, "file output enabled without file specified"));
+        };
+        f.write_all(line.as_bytes())?;
+        self.rotation.len += line.len() as u64;
+        Ok(())
     }
 }
 
-#[cfg(not(feature = "async"))]
-impl Logger {
+/// Forward read-only methods of an [AsyncLogger] to its wrapped [Logger].
+#[cfg(feature = "async")]
+macro_rules! forward_getters {
+    ($($(#[$doc:meta])* fn $name:ident(&self) -> $ret:ty;)+) => {
+        $(
+            $(#[$doc])*
+            pub fn $name(&self) -> $ret {
+                self.logger.$name()
+            }
+        )+
+    };
+}
+
+#[cfg(feature = "async")]
+impl AsyncLogger {
+    /**
+        Create a new asynchronous logger.
+
+        The logger is initialised with a log index of 0.
+     */
+    pub fn new() -> Self {
+        AsyncLogger { logger: Logger::new(), file: None }
+    }
+
+    /// Set the minimum level for both stderr and file output; see [Logger::set_level].
+    pub fn set_level(&mut self, lvl: LogLevel) -> &mut Self {
+        self.logger.set_level(lvl);
+        self
+    }
+
+    /// Set the minimum level for stderr output.
+    pub fn set_stderr_level(&mut self, lvl: LogLevel) -> &mut Self {
+        self.logger.set_stderr_level(lvl);
+        self
+    }
+
+    /// Set the minimum level for file output.
+    pub fn set_file_level(&mut self, lvl: LogLevel) -> &mut Self {
+        self.logger.set_file_level(lvl);
+        self
+    }
+
+    /// Add an output to the logger, alongside stderr and the file; see [Logger::add_sink].
+    pub fn add_sink<S: Sink + 'static>(&mut self, sink: S, lvl: LogLevel, format: Format) -> &mut Self {
+        self.logger.add_sink(sink, lvl, format);
+        self
+    }
+
+    /// Remove all outputs added with [add_sink](AsyncLogger::add_sink).
+    pub fn clear_sinks(&mut self) -> &mut Self {
+        self.logger.clear_sinks();
+        self
+    }
+
+    /// Set the index of the next message; see [Logger::set_index].
+    pub fn set_index(&mut self, index: u64) -> &mut Self {
+        self.logger.set_index(index);
+        self
+    }
+
+    /// Reset the index of the next message to 0.
+    pub fn reset_index(&mut self) -> &mut Self {
+        self.logger.reset_index();
+        self
+    }
+
+    forward_getters! {
+        /// Get the minimum level for stderr output.
+        fn stderr_level(&self) -> LogLevel;
+        /// Get the minimum level for file output.
+        fn file_level(&self) -> LogLevel;
+        /// Get the index of the next message.
+        fn index(&self) -> u64;
+        /// Whether the log index is shown in the header.
+        fn shows_index(&self) -> bool;
+        /// Whether the level symbol is shown in the header.
+        fn shows_symbol(&self) -> bool;
+        /// Whether lines are coloured; see also [color_mode](AsyncLogger::color_mode).
+        fn shows_color(&self) -> bool;
+        /// Whether lines are bold.
+        fn shows_bold(&self) -> bool;
+        /// Whether the timer is shown in the header.
+        fn shows_timer(&self) -> bool;
+        /// The clock of the wall-clock time shown in the header, if any.
+        fn timestamp(&self) -> Option<Clock>;
+        /// The `strftime`-style format of the wall-clock time, if not RFC 3339.
+        fn timestamp_format(&self) -> Option<&str>;
+        /// The time the timer started at, if any.
+        fn timer(&self) -> Option<std::time::Instant>;
+        /// Whether messages are logged to a file.
+        fn logs_to_file(&self) -> bool;
+        /// Whether messages are logged only to the file, and not to the console.
+        fn file_only(&self) -> bool;
+        /// The path of the log file, if it was opened by path.
+        fn file_path(&self) -> Option<&Path>;
+        /// The size the log file is rotated at, if any.
+        fn max_file_size(&self) -> Option<u64>;
+        /// How often the log file is rotated.
+        fn rotation(&self) -> Rotation;
+        /// The number of rotated log files kept.
+        fn keep_files(&self) -> usize;
+        /// The console stream(s) written to.
+        fn console(&self) -> Stream;
+        /// The output format for the console.
+        fn stderr_format(&self) -> Format;
+        /// The output format for the file.
+        fn file_format(&self) -> Format;
+        /// When escape sequences are written to the console.
+        fn color_mode(&self) -> ColorMode;
+        /// The colours and symbols of each level.
+        fn theme(&self) -> &Theme;
+        /// The pattern text lines are laid out with, if any.
+        fn pattern(&self) -> Option<&str>;
+        /// What happens when a log line cannot be written.
+        fn write_failure(&self) -> WriteFailure;
+        /// The level at or above which every message is followed by a flush.
+        fn flush_level(&self) -> LogLevel;
+        /// The longest time buffered output is kept before a message flushes it, if set.
+        fn flush_interval(&self) -> Option<Duration>;
+        /// Whether the log index is 64-bit rather than 16-bit.
+        fn wide_index(&self) -> bool;
+        /// The number of digits the log index is padded to.
+        fn index_width(&self) -> usize;
+        /// The radix the log index is written in.
+        fn index_radix(&self) -> Radix;
+    }
+
+    /// The wrapped logger, whose settings the options of a config are compared against.
+    #[cfg(feature = "config")]
+    pub(crate) fn logger(&self) -> &Logger {
+        &self.logger
+    }
+
     /**
         Configure the logger with options.
         
@@ -177,44 +1678,72 @@ impl Logger {
         # Arguments
         - `opts`: an array of [Options]
      */
-    pub fn cfg(&mut self, opts: &[Options]) -> Result<&mut Self, io::Error> {
+    pub async fn cfg(&mut self, opts: &[Options<'_>]) -> Result<&mut Self, io::Error> {
         for &e in opts {
             match e {
-                Options::NoIndex =>   self.flags |= 0b00000001,
-                Options::NoSymbol =>  self.flags |= 0b00000010,
-                Options::NoColor =>   self.flags |= 0b00000100,
-                Options::NoBold =>    self.flags |= 0b00001000,
-                Options::Plain =>     self.flags |= 0b00001100,
-                Options::Basic =>     self.flags |= 0b00001111,
-                Options::File => {
-                    self.flags |= 0b00010000;
-                    self.file = Some(
-                        io::BufWriter::new(
-                        File::create("forestry.log")?)
-                    );
-                },
+                Options::File => self.open(Path::new(DEFAULT_FILE), false).await?,
                 Options::FileAt(f) => {
-                    self.flags |= 0b00010000;
-                    self.file = Some(
-                        io::BufWriter::new(
-                        f.try_clone()?)
-                    );
-                },
-                Options::FileOnly =>  self.flags |= 0b00100000,
-                Options::Timer => {
-                    self.flags |= 0b01000000;
-                    self.timer = Some(std::time::Instant::now());
+                    self.file = Some(tokio::io::BufWriter::new(tokio::fs::File::from_std(f.try_clone()?)));
+                    self.logger.file_opened(None, 0);
                 },
-                Options::TimerAt(t) => {
-                    self.flags |= 0b01000000;
-                    self.timer = Some(*t);
-                },
-                Options::Reset =>     self.flags &= 0b00000000,
+                Options::FilePath(p) => self.open(p, false).await?,
+                Options::FileAppend(p) => self.open(p, true).await?,
+                Options::NoFile => self.close().await?,
+                Options::Reset => self.reset().await?,
+                e => self.logger.apply_option(e)?,
             }
         }
         Ok(self)
     }
 
+    /**
+        Log a message.
+        
+        The message is logged as a TRACE message.
+        It is hidden unless the minimum level is `Trace`.
+        
+        # Arguments
+        - `s`: The message to log.
+        
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         # #[tokio::main(flavor = "current_thread")] async fn main() {
+         let mut log = AsyncLogger::new();
+         log.set_level(LogLevel::Trace);
+         log.trace("trace").await;           // Output: [0000:.] trace
+         # }
+        ```
+     */
+    pub async fn trace(&mut self, s: &str) -> &mut Self {
+        self.print(LogLevel::Trace, s, &[]).await;
+        self
+    }
+
+    /**
+        Log a message.
+        
+        The message is logged as a DEBUG message.
+        It is hidden unless the minimum level is `Debug` or lower.
+        
+        # Arguments
+        - `s`: The message to log.
+        
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         # #[tokio::main(flavor = "current_thread")] async fn main() {
+         let mut log = AsyncLogger::new();
+         log.set_level(LogLevel::Debug);
+         log.debug("debug").await;           // Output: [0000:?] debug
+         # }
+        ```
+     */
+    pub async fn debug(&mut self, s: &str) -> &mut Self {
+        self.print(LogLevel::Debug, s, &[]).await;
+        self
+    }
+
     /**
         Log a message.
         
@@ -226,310 +1755,556 @@ impl Logger {
         # Example
         ```rust
          use forestry::prelude::*;
-         let mut log = Logger::new();
-         log.info("info");            // Output: [0000:*] info
+         # #[tokio::main(flavor = "current_thread")] async fn main() {
+         let mut log = AsyncLogger::new();
+         log.info("info").await;            // Output: [0000:*] info
+         # }
+        ```
+     */
+    pub async fn info(&mut self, s: &str) -> &mut Self {
+        self.print(LogLevel::Info, s, &[]).await;
+        self
+    }
+
+    /**
+        Log a message.
+        
+        The message is logged as a WARN message.
+        
+        # Arguments
+        - `s`: The message to log.
+        
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         # #[tokio::main(flavor = "current_thread")] async fn main() {
+         let mut log = AsyncLogger::new();
+         log.warn("warn").await;            // Output: [0000:~] warn
+         # }
+        ```
+     */
+    pub async fn warn(&mut self, s: &str) -> &mut Self {
+        self.print(LogLevel::Warn, s, &[]).await;
+        self
+    }
+
+    /**
+        Log a message.
+        
+        The message is logged as an ERROR message.
+        
+        # Arguments
+        - `s`: The message to log.
+        
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         # #[tokio::main(flavor = "current_thread")] async fn main() {
+         let mut log = AsyncLogger::new();
+         log.error("error").await;           // Output: [0000:!] error
+         # }
+        ```
+     */
+    pub async fn error(&mut self, s: &str) -> &mut Self {
+        self.print(LogLevel::Error, s, &[]).await;
+        self
+    }
+
+    /**
+        Log a message.
+        
+        The message is logged as a SUCCESS message.
+        
+        # Arguments
+        - `s`: The message to log.
+        
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         # #[tokio::main(flavor = "current_thread")] async fn main() {
+         let mut log = AsyncLogger::new();
+         log.success("success").await;         // Output: [0000:+] success
+         # }
+        ```
+     */
+    pub async fn success(&mut self, s: &str) -> &mut Self {
+        self.print(LogLevel::Success, s, &[]).await;
+        self
+    }
+
+    /**
+        Log a message,
+
+        The message is logged as a CRITICAL message.
+
+        # Arguments
+        - `s`: The message to log.
+
+        # Example
+        ```rust
+         use forestry::prelude::*;
+         # #[tokio::main(flavor = "current_thread")] async fn main() {
+         let mut log = AsyncLogger::new();
+         log.critical("critical").await;        // Output: [0000:%] critical
+         # }
         ```
+    */
+    pub async fn critical(&mut self, s: &str) -> &mut Self {
+        self.print(LogLevel::Critical, s, &[]).await;
+        self
+    }
+
+    /**
+        Log a message at the given level.
+        
+        # Arguments
+        - `lvl`: The level of the message.
+        - `s`: The message to log.
+     */
+    pub async fn log(&mut self, lvl: LogLevel, s: &str) -> &mut Self {
+        self.print(lvl, s, &[]).await;
+        self
+    }
+
+    /**
+        Log a message with key/value fields at the given level.
+        
+        # Arguments
+        - `lvl`: The level of the message.
+        - `s`: The message to log.
+        - `kv`: The fields to attach to the message.
      */
-    pub fn info(&mut self, s: &str) -> &mut Self {
-        self.print(LogLevel::Info, s)
+    pub async fn log_kv(&mut self, lvl: LogLevel, s: &str, kv: &[(&str, Value<'_>)]) -> &mut Self {
+        self.print(lvl, s, kv).await;
+        self
     }
 
     /**
-        Log a message.
-        
-        The message is logged as a WARN message.
-        
+        Log a message at the given level, or return the first write which failed.
+
+        Unlike [log](AsyncLogger::log), a failed write is returned rather than handled by the
+        [WriteFailure] policy. The other outputs are still written to, and the log index
+        still advances.
+
         # Arguments
+        - `lvl`: The level of the message.
         - `s`: The message to log.
-        
+
         # Example
         ```rust
          use forestry::prelude::*;
-         let mut log = Logger::new();
-         log.warn("warn");            // Output: [0000:~] warn
+         # #[tokio::main(flavor = "current_thread")] async fn main() {
+         let mut log = AsyncLogger::new();
+         log.try_log(LogLevel::Info, "info").await.unwrap();
+         # }
         ```
      */
-    pub fn warn(&mut self, s: &str) -> &mut Self {
-        self.print(LogLevel::Warn, s)
+    pub async fn try_log(&mut self, lvl: LogLevel, s: &str) -> Result<&mut Self, Error> {
+        self.try_print_target(lvl, "", s, &[]).await
+    }
+
+    /// Log a message with key/value fields at the given level, or return the first write which failed; see [try_log](AsyncLogger::try_log).
+    pub async fn try_log_kv(&mut self, lvl: LogLevel, s: &str, kv: &[(&str, Value<'_>)]) -> Result<&mut Self, Error> {
+        self.try_print_target(lvl, "", s, kv).await
+    }
+
+    /// Log a TRACE message, or return the first write which failed; see [try_log](AsyncLogger::try_log).
+    pub async fn try_trace(&mut self, s: &str) -> Result<&mut Self, Error> {
+        self.try_print_target(LogLevel::Trace, "", s, &[]).await
+    }
+
+    /// Log a DEBUG message, or return the first write which failed; see [try_log](AsyncLogger::try_log).
+    pub async fn try_debug(&mut self, s: &str) -> Result<&mut Self, Error> {
+        self.try_print_target(LogLevel::Debug, "", s, &[]).await
+    }
+
+    /// Log an INFO message, or return the first write which failed; see [try_log](AsyncLogger::try_log).
+    pub async fn try_info(&mut self, s: &str) -> Result<&mut Self, Error> {
+        self.try_print_target(LogLevel::Info, "", s, &[]).await
+    }
+
+    /// Log a SUCCESS message, or return the first write which failed; see [try_log](AsyncLogger::try_log).
+    pub async fn try_success(&mut self, s: &str) -> Result<&mut Self, Error> {
+        self.try_print_target(LogLevel::Success, "", s, &[]).await
+    }
+
+    /// Log a WARN message, or return the first write which failed; see [try_log](AsyncLogger::try_log).
+    pub async fn try_warn(&mut self, s: &str) -> Result<&mut Self, Error> {
+        self.try_print_target(LogLevel::Warn, "", s, &[]).await
+    }
+
+    /// Log an ERROR message, or return the first write which failed; see [try_log](AsyncLogger::try_log).
+    pub async fn try_error(&mut self, s: &str) -> Result<&mut Self, Error> {
+        self.try_print_target(LogLevel::Error, "", s, &[]).await
+    }
+
+    /// Log a CRITICAL message, or return the first write which failed; see [try_log](AsyncLogger::try_log).
+    pub async fn try_critical(&mut self, s: &str) -> Result<&mut Self, Error> {
+        self.try_print_target(LogLevel::Critical, "", s, &[]).await
     }
 
     /**
-        Log a message.
+        Log a message with key/value fields.
         
-        The message is logged as an ERROR message.
+        The message is logged as a TRACE message, followed by its fields.
         
         # Arguments
         - `s`: The message to log.
+        - `kv`: The fields to attach to the message.
         
         # Example
         ```rust
          use forestry::prelude::*;
-         let mut log = Logger::new();
-         log.error("error");           // Output: [0000:!] error
+         # #[tokio::main(flavor = "current_thread")] async fn main() {
+         let mut log = AsyncLogger::new();
+         log.set_level(LogLevel::Trace);
+         log.trace_kv("trace", &[("status", 200.into())]).await;   // Output: [0000:.] trace status=200
+         # }
         ```
      */
-    pub fn error(&mut self, s: &str) -> &mut Self {
-        self.print(LogLevel::Error, s)
+    pub async fn trace_kv(&mut self, s: &str, kv: &[(&str, Value<'_>)]) -> &mut Self {
+        self.print(LogLevel::Trace, s, kv).await;
+        self
     }
 
     /**
-        Log a message.
+        Log a message with key/value fields.
         
-        The message is logged as a SUCCESS message.
+        The message is logged as a DEBUG message, followed by its fields.
         
         # Arguments
         - `s`: The message to log.
+        - `kv`: The fields to attach to the message.
         
         # Example
         ```rust
          use forestry::prelude::*;
-         let mut log = Logger::new();
-         log.success("success");         // Output: [0000:+] success
+         # #[tokio::main(flavor = "current_thread")] async fn main() {
+         let mut log = AsyncLogger::new();
+         log.set_level(LogLevel::Debug);
+         log.debug_kv("debug", &[("status", 200.into())]).await;   // Output: [0000:?] debug status=200
+         # }
         ```
      */
-    pub fn success(&mut self, s: &str) -> &mut Self {
-        self.print(LogLevel::Success, s)
+    pub async fn debug_kv(&mut self, s: &str, kv: &[(&str, Value<'_>)]) -> &mut Self {
+        self.print(LogLevel::Debug, s, kv).await;
+        self
     }
 
     /**
-        Log a message,
-
-        The message is logged as a CRITICAL message.
-
+        Log a message with key/value fields.
+        
+        The message is logged as an INFO message, followed by its fields.
+        
         # Arguments
         - `s`: The message to log.
-
+        - `kv`: The fields to attach to the message.
+        
         # Example
         ```rust
          use forestry::prelude::*;
-         let mut log = Logger::new();
-         log.critical("critical");        // Output: [0000:%] critical
+         # #[tokio::main(flavor = "current_thread")] async fn main() {
+         let mut log = AsyncLogger::new();
+         log.info_kv("info", &[("status", 200.into())]).await;   // Output: [0000:*] info status=200
+         # }
         ```
-    */
-    pub fn critical(&mut self, s: &str) -> &mut Self {
-        self.print(LogLevel::Critical, s)
-    }
-
-    fn print(&mut self, lvl: LogLevel, string: &str) -> &mut Self {
-        if self.flags & 0b00100000 == 0 {
-            let mut s: String = self.fmt_header(lvl);
-            s.push_str(&self.fmt_string(lvl, string));
-            s.push('\n');
-            io::stderr().write_all(s.as_bytes()).unwrap();
-        }
-
-        if self.flags & 0b00010000 != 0 {
-            let temp = self.flags & 0b00001100;
-            self.flags |= 0b00001100;
-            // invoke buffered print here while formatting is temporarily plain
-            let mut plain = String::from("");
-            plain.push_str(&self.fmt_header(lvl)); 
-            plain.push_str(&self.fmt_string(lvl, string));
-            plain.push('\n');
-            if self.file.is_none() {
-                self.warn("File output enabled without file specified.");
-            } else {
-                self.file
-                    .as_mut()
-                    .unwrap()
-                    .write(plain.as_bytes())
-                    .unwrap();
-            }
-            self.flags &= 0b11110011;
-            self.flags |= temp;
-        }
-
-        self.index = self.index.wrapping_add(1);
-        if self.index == 0 {
-            self.warn("Log index overflowed; log index may be inaccurate.");
-        }
-        self
-    }
-}
-
-#[cfg(feature = "async")]
-impl Logger {
-    /**
-        Configure the logger with options.
-        
-        See [Options] for more details.
-        
-        # Arguments
-        - `opts`: an array of [Options]
      */
-    pub async fn cfg(&mut self, opts: &[Options<'_>]) -> Result<&mut Self, io::Error> {
-        for &e in opts {
-            match e {
-                Options::NoIndex =>   self.flags |= 0b00000001,
-                Options::NoSymbol =>  self.flags |= 0b00000010,
-                Options::NoColor =>   self.flags |= 0b00000100,
-                Options::NoBold =>    self.flags |= 0b00001000,
-                Options::Plain =>     self.flags |= 0b00001100,
-                Options::Basic =>     self.flags |= 0b00001111,
-                Options::File => {
-                    self.flags |= 0b00010000;
-                    self.file = Some(
-                        io::BufWriter::new(
-                        File::create("forestry.log").await?)
-                    );
-                },
-                Options::FileAt(f) => {
-                    self.flags |= 0b00010000;
-                    self.file = Some(
-                        io::BufWriter::new(
-                        f.try_clone().await?)
-                    );
-                },
-                Options::FileOnly =>  self.flags |= 0b00100000,
-                Options::Timer => {
-                    self.flags |= 0b01000000;
-                    self.timer = Some(std::time::Instant::now());
-                },
-                Options::TimerAt(t) => {
-                    self.flags |= 0b01000000;
-                    self.timer = Some(*t);
-                },
-                Options::Reset =>     self.flags &= 0b00000000,
-            }
-        }
-        Ok(self)
+    pub async fn info_kv(&mut self, s: &str, kv: &[(&str, Value<'_>)]) -> &mut Self {
+        self.print(LogLevel::Info, s, kv).await;
+        self
     }
 
     /**
-        Log a message.
+        Log a message with key/value fields.
         
-        The message is logged as an INFO message.
+        The message is logged as a WARN message, followed by its fields.
         
         # Arguments
         - `s`: The message to log.
+        - `kv`: The fields to attach to the message.
         
         # Example
         ```rust
          use forestry::prelude::*;
-         let mut log = Logger::new();
-         log.info("info");            // Output: [0000:*] info
+         # #[tokio::main(flavor = "current_thread")] async fn main() {
+         let mut log = AsyncLogger::new();
+         log.warn_kv("warn", &[("status", 200.into())]).await;   // Output: [0000:~] warn status=200
+         # }
         ```
      */
-    pub async fn info(&mut self, s: &str) -> &mut Self {
-        self.print(LogLevel::Info, s).await;
+    pub async fn warn_kv(&mut self, s: &str, kv: &[(&str, Value<'_>)]) -> &mut Self {
+        self.print(LogLevel::Warn, s, kv).await;
         self
     }
 
     /**
-        Log a message.
+        Log a message with key/value fields.
         
-        The message is logged as a WARN message.
+        The message is logged as an ERROR message, followed by its fields.
         
         # Arguments
         - `s`: The message to log.
+        - `kv`: The fields to attach to the message.
         
         # Example
         ```rust
          use forestry::prelude::*;
-         let mut log = Logger::new();
-         log.warn("warn");            // Output: [0000:~] warn
+         # #[tokio::main(flavor = "current_thread")] async fn main() {
+         let mut log = AsyncLogger::new();
+         log.error_kv("error", &[("status", 200.into())]).await;   // Output: [0000:!] error status=200
+         # }
         ```
      */
-    pub async fn warn(&mut self, s: &str) -> &mut Self {
-        self.print(LogLevel::Warn, s).await;
+    pub async fn error_kv(&mut self, s: &str, kv: &[(&str, Value<'_>)]) -> &mut Self {
+        self.print(LogLevel::Error, s, kv).await;
         self
     }
 
     /**
-        Log a message.
+        Log a message with key/value fields.
         
-        The message is logged as an ERROR message.
+        The message is logged as a SUCCESS message, followed by its fields.
         
         # Arguments
         - `s`: The message to log.
+        - `kv`: The fields to attach to the message.
         
         # Example
         ```rust
          use forestry::prelude::*;
-         let mut log = Logger::new();
-         log.error("error");           // Output: [0000:!] error
+         # #[tokio::main(flavor = "current_thread")] async fn main() {
+         let mut log = AsyncLogger::new();
+         log.success_kv("success", &[("status", 200.into())]).await;   // Output: [0000:+] success status=200
+         # }
         ```
      */
-    pub async fn error(&mut self, s: &str) -> &mut Self {
-        self.print(LogLevel::Error, s).await;
+    pub async fn success_kv(&mut self, s: &str, kv: &[(&str, Value<'_>)]) -> &mut Self {
+        self.print(LogLevel::Success, s, kv).await;
         self
     }
 
     /**
-        Log a message.
+        Log a message with key/value fields.
         
-        The message is logged as a SUCCESS message.
+        The message is logged as a CRITICAL message, followed by its fields.
         
         # Arguments
         - `s`: The message to log.
+        - `kv`: The fields to attach to the message.
         
         # Example
         ```rust
          use forestry::prelude::*;
-         let mut log = Logger::new();
-         log.success("success");         // Output: [0000:+] success
+         # #[tokio::main(flavor = "current_thread")] async fn main() {
+         let mut log = AsyncLogger::new();
+         log.critical_kv("critical", &[("status", 200.into())]).await;   // Output: [0000:%] critical status=200
+         # }
         ```
      */
-    pub async fn success(&mut self, s: &str) -> &mut Self {
-        self.print(LogLevel::Success, s).await;
+    pub async fn critical_kv(&mut self, s: &str, kv: &[(&str, Value<'_>)]) -> &mut Self {
+        self.print(LogLevel::Critical, s, kv).await;
         self
     }
 
     /**
-        Log a message,
+        Write out any buffered output to the log file and sinks.
 
-        The message is logged as a CRITICAL message.
+        The file is buffered, so lines may not reach it until it is flushed. This happens
+        automatically after messages at or above the flush level (`Error` by default; see
+        [Options::FlushLevel]), and optionally at an interval (see [Options::FlushInterval]).
+        Dropping the logger can only write out the buffer if the file is idle, so call this or
+        [shutdown](AsyncLogger::shutdown) before the runtime stops.
 
-        # Arguments
-        - `s`: The message to log.
+        Every output is flushed even if one fails; the error is that of the first which failed.
+     */
+    pub async fn flush(&mut self) -> Result<&mut Self, Error> {
+        match self.flush_all().await.into_iter().next() {
+            Some(e) => Err(e),
+            None => Ok(self),
+        }
+    }
 
-        # Example
-        ```rust
-         use forestry::prelude::*;
-         let mut log = Logger::new();
-         log.critical("critical");        // Output: [0000:%] critical
-        ```
-    */
-    pub async fn critical(&mut self, s: &str) -> &mut Self {
-        self.print(LogLevel::Critical, s).await;
+    /**
+        Flush and close the log file, and flush every sink.
+
+        Logging to the file stops, as with [Options::NoFile]; the console and sinks are
+        still written to.
+     */
+    pub async fn shutdown(&mut self) -> Result<&mut Self, Error> {
+        let mut failed = Vec::new();
+        self.logger.file_closed();
+        if let Some(mut f) = self.file.take() {
+            if let Err(e) = f.shutdown().await {
+                failed.push(Error::File(e));
+            }
+        }
+        self.logger.flush_sinks(&mut failed);
+        match failed.into_iter().next() {
+            Some(e) => Err(e),
+            None => Ok(self),
+        }
+    }
+
+    /// Flush the file and every sink, returning the failures.
+    async fn flush_all(&mut self) -> Vec<Error> {
+        let mut failed = Vec::new();
+        if let Some(f) = self.file.as_mut() {
+            if let Err(e) = f.flush().await {
+                failed.push(Error::File(e));
+            }
+        }
+        self.logger.flush_sinks(&mut failed);
+        failed
+    }
+
+    /// Flush and close the log file, and stop logging to it.
+    async fn close(&mut self) -> Result<(), io::Error> {
+        self.logger.file_closed();
+        if let Some(mut f) = self.file.take() {
+            f.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// Close the log file and restore every setting to its default.
+    async fn reset(&mut self) -> Result<(), io::Error> {
+        self.close().await?;
+        self.logger.restore_defaults();
+        Ok(())
+    }
+
+    /**
+        Open the log file at `path`, creating any missing parent directories.
+
+        The current log file is flushed and dropped first. The new file is appended to if
+        `append` is set, and truncated otherwise.
+     */
+    async fn open(&mut self, path: &Path, append: bool) -> Result<(), io::Error> {
+        if let Some(mut f) = self.file.take() {
+            f.flush().await?;
+        }
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let f = tokio::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .append(append)
+            .truncate(!append)
+            .open(path)
+            .await?;
+        let len = f.metadata().await?.len();
+        self.file = Some(tokio::io::BufWriter::new(f));
+        self.logger.file_opened(Some(path.to_path_buf()), len);
+        self.logger.rotation.append = append;
+        Ok(())
+    }
+
+    /**
+        Move the log file aside and start a new one.
+
+        `forestry.log` becomes `forestry.log.1`, `forestry.log.1` becomes
+        `forestry.log.2`, and so on; files beyond the `KeepFiles` limit, including any
+        left from a higher limit, are removed.
+     */
+    async fn rotate(&mut self) -> Result<(), io::Error> {
+        let Some(path) = self.logger.rotation.path.clone() else { return Ok(()) };
+        if let Some(mut f) = self.file.take() {
+            f.flush().await?;
+        }
+        if self.logger.rotation.keep > 0 {
+            for (from, to) in self.logger.rotation.shifts(&path) {
+                match tokio::fs::rename(&from, &to).await {
+                    Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                    _ => {},
+                }
+            }
+        }
+        for stale in self.logger.rotation.stale(&path) {
+            match tokio::fs::remove_file(&stale).await {
+                Err(e) if e.kind() == io::ErrorKind::NotFound => break,
+                r => r?,
+            }
+        }
+        self.file = Some(tokio::io::BufWriter::new(tokio::fs::File::create(&path).await?));
+        self.logger.rotation.opened(Some(path), 0);
+        Ok(())
+    }
+
+    pub(crate) async fn print(&mut self, lvl: LogLevel, string: &str, kv: &[(&str, Value<'_>)]) -> &mut Self {
+        self.print_target(lvl, "", string, kv).await
+    }
+
+    /// Log a message from the given target, such as a module path.
+    pub(crate) async fn print_target(&mut self, lvl: LogLevel, target: &str, string: &str, kv: &[(&str, Value<'_>)]) -> &mut Self {
+        for (e, line) in self.emit(lvl, target, string, kv).await {
+            self.logger.write_failed(e, &line);
+        }
         self
     }
 
-    async fn print(&mut self, lvl: LogLevel, string: &str) -> &mut Self {
-        if self.flags & 0b00100000 == 0 {
-            let mut s: String = self.fmt_header(lvl);
-            s.push_str(&self.fmt_string(lvl, string));
-            s.push('\n');
-            io::stderr().write_all(s.as_bytes()).await.unwrap();
+    /// Log a message from the given target, returning the first write which failed.
+    pub(crate) async fn try_print_target(&mut self, lvl: LogLevel, target: &str, string: &str, kv: &[(&str, Value<'_>)]) -> Result<&mut Self, Error> {
+        match self.emit(lvl, target, string, kv).await.into_iter().next() {
+            Some((e, _)) => Err(e),
+            None => Ok(self),
+        }
+    }
+
+    /**
+        Write a message to every output it goes to, and advance the log index.
+
+        A failed write does not stop the others; the failures are returned along
+        with the lines which were not written.
+     */
+    async fn emit(&mut self, lvl: LogLevel, target: &str, string: &str, kv: &[(&str, Value<'_>)]) -> Vec<(Error, String)> {
+        if !self.logger.enabled(lvl) {
+            return Vec::new();
+        }
+        let mut failed = self.write_outputs(lvl, target, string, kv).await;
+        if !self.logger.next_index() {
+            failed.extend(self.write_outputs(LogLevel::Warn, "", INDEX_OVERFLOW, &[]).await);
+            self.logger.next_index();
         }
+        failed
+    }
 
-        if self.flags & 0b00010000 != 0 {
-            let temp = self.flags & 0b00001100;
-            self.flags |= 0b00001100;
-            // invoke buffered print here while formatting is temporarily plain
-            let mut plain = String::from("");
-            plain.push_str(&self.fmt_header(lvl)); 
-            plain.push_str(&self.fmt_string(lvl, string));
-            plain.push('\n');
-            if self.file.is_none() {
-                eprintln!("File output enabled without file specified.");
+    /// Write a message to every output it goes to, and flush them if due, without advancing the log index.
+    async fn write_outputs(&mut self, lvl: LogLevel, target: &str, string: &str, kv: &[(&str, Value<'_>)]) -> Vec<(Error, String)> {
+        let mut failed = Vec::new();
+        if let Some((s, stdout)) = self.logger.console_line(lvl, target, string, kv) {
+            let written = if stdout {
+                tokio::io::stdout().write_all(s.as_bytes()).await
             } else {
-                self.file
-                    .as_mut()
-                    .unwrap()
-                    .write(plain.as_bytes())
-                    .await
-                    .unwrap();
+                tokio::io::stderr().write_all(s.as_bytes()).await
+            };
+            if let Err(e) = written {
+                failed.push((Error::Console(e), s));
             }
-            self.flags &= 0b11110011;
-            self.flags |= temp;
         }
+        if let Some(plain) = self.logger.file_line(lvl, target, string, kv) {
+            if let Err(e) = self.write_file(&plain).await {
+                failed.push((Error::File(e), plain));
+            }
+        }
+        self.logger.write_sinks(lvl, target, string, kv, &mut failed);
+        if self.logger.flush_due(lvl) {
+            failed.extend(self.flush_all().await.into_iter().map(|e| (e, String::new())));
+        }
+        failed
+    }
 
-        self.index = self.index.wrapping_add(1);
-        if self.index == 0 {
-            eprintln!("Log index overflowed; log index may be inaccurate.");
+    /// Write a line to the log file, rotating it first if it is due.
+    async fn write_file(&mut self, line: &str) -> Result<(), io::Error> {
+        if self.logger.rotation.due(line.len()) {
+            self.rotate().await?;
         }
-        self
+        let Some(f) = self.file.as_mut() else {
+            return Err(io::Error::new(io::ErrorKind::NotFound, "file output enabled without file specified"));
+        };
+        f.write_all(line.as_bytes()).await?;
+        self.logger.rotation.len += line.len() as u64;
+        Ok(())
     }
 }
 
@@ -540,14 +2315,46 @@ impl Logger {
     - `NoSymbol`: Removes the log type symbol.
     - `NoColor`: Removes all colour sequences.
     - `NoBold`: Removes all bold sequences.
+    - `Index`, `Symbol`, `Color`, `Bold`: Undo `NoIndex`, `NoSymbol`, `NoColor` and `NoBold` respectively.
     - `Plain`: Removes all formatting escape characters.
+    - `ColorAuto`: Writes escape sequences only to a terminal, honouring `NO_COLOR`, `CLICOLOR` and `CLICOLOR_FORCE` (default).
+    - `ColorAlways`: Always writes escape sequences to the console.
+    - `ColorNever`: Never writes escape sequences.
+    - `UseTheme(&'a Theme)`: Sets the colours and symbols of each level; see [Theme].
+    - `Pattern(&'a str)`: Lays out text lines with a pattern such as `"{time:%H:%M:%S} {level:>5} [{index:04x}] {target}: {msg}"`; see [Options::Pattern].
+    - `NoPattern`: Removes the pattern, restoring the header set by the other options.
+    - `WideIndex`: Uses a 64-bit log index, which does not wrap after 65 536 messages.
+    - `NarrowIndex`: Uses a 16-bit log index again (default).
+    - `IndexWidth(usize)`: Pads the log index with zeroes to the given number of digits (default 4).
+    - `IndexRadix(Radix)`: Writes the log index in hexadecimal (default) or decimal.
     - `Basic`: Turns this into a bare `eprintln!()` call.
     - `File`: Logs to the default file (`forestry.log`).
     - `FileAt(&'a std::fs::File)`: Logs to a specified file.
-    - `FileOnly`: Only logs to the file; requires `File` or `FileAt`.
+    - `FilePath(&'a Path)`: Logs to the file at a path, replacing its contents.
+    - `FileAppend(&'a Path)`: Logs to the end of the file at a path.
+    - `FileOnly`: Only logs to the file; requires `File`, `FileAt`, `FilePath` or `FileAppend`.
+    - `NoFile`: Stops logging to the file, flushing and closing it; also undoes `FileOnly`.
     - `Time`: Include a timestamp in the log.
     - `TimerAt (&'a std::time::Instant)`: Attach an existing timestamp to the log (to allow the use of a runtime timer within one's own program as the timer).
-    - `Reset`: Resets the logger's formatter to default settings.
+    - `NoTimer`: Removes the timer.
+    - `Timestamp(Clock)`: Include the wall-clock time in the log, in UTC or the local time zone, as RFC 3339; undoes `TimestampFormat`.
+    - `TimestampFormat(&'a str)`: Format the wall-clock time with a `strftime`-style format string instead of RFC 3339; implies `Timestamp`.
+    - `NoTimestamp`: Removes the wall-clock time.
+    - `MaxFileSize(u64)`: Rotate the log file before it grows past the given number of bytes; a file given with `FileAt` has no path, so it is never rotated.
+    - `Rotate(Rotation)`: Rotate the log file every hour or day; likewise ignored for a file given with `FileAt`.
+    - `KeepFiles(usize)`: Keep at most the given number of rotated log files (default 5); older ones are removed at the next rotation.
+    - `NoRotation`: Stops rotating the log file.
+    - `Console(Stream)`: Sets the console stream(s) written to instead of stderr; also undoes `FileOnly`.
+    - `StderrFormat(Format)`: Sets the output format for stderr.
+    - `FileFormat(Format)`: Sets the output format for the file.
+    - `Level(LogLevel)`: Only log messages at or above the given level.
+    - `StderrLevel(LogLevel)`: Only log messages at or above the given level to stderr.
+    - `FileLevel(LogLevel)`: Only log messages at or above the given level to the file.
+    - `OnWriteFailure(WriteFailure)`: Sets what happens when a line cannot be written: ignore it, report it on stderr (default), or panic.
+    - `FlushLevel(LogLevel)`: Flushes the file and sinks after every message at or above the given level (default `Error`).
+    - `FlushInterval(Duration)`: Flushes the file and sinks after a message if they were last flushed at least this long ago.
+    - `NoFlushInterval`: Removes the flush interval.
+    - `Reset`: Resets every setting to its default, closing the log file; the log index and sinks are kept.
  */
 #[derive(Copy, Clone)]
 pub enum Options <'a> {
@@ -559,29 +2366,410 @@ pub enum Options <'a> {
     NoColor,
     /// Removes bold/highlighting.
     NoBold,
+    /// Shows the incrementing log index (default).
+    Index,
+    /// Shows the log type symbol (default).
+    Symbol,
+    /// Shows colours (default).
+    Color,
+    /// Shows bold/highlighting (default).
+    Bold,
     /// Removes all formatting escape characters.
     Plain,
+    /// Writes escape sequences only to a terminal, honouring `NO_COLOR`, `CLICOLOR` and `CLICOLOR_FORCE` (default).
+    ColorAuto,
+    /// Always writes escape sequences to the console.
+    ColorAlways,
+    /// Never writes escape sequences.
+    ColorNever,
+    /// Sets the colours and symbols of each level.
+    UseTheme(&'a Theme),
+    /// Uses a 64-bit log index, which does not wrap after 65 536 messages.
+    WideIndex,
+    /// Uses a 16-bit log index (default); the index is truncated to 16 bits.
+    NarrowIndex,
+    /// Pads the log index with zeroes to the given number of digits (default 4).
+    IndexWidth(usize),
+    /// Writes the log index in hexadecimal (default) or decimal.
+    IndexRadix(Radix),
+    /**
+        Lays out text lines with a pattern, instead of the header set by the other options.
+
+        The pattern is literal text with `{name}` or `{name:spec}` placeholders, as with
+        [format!]; `{{` and `}}` are literal braces. The placeholders are:
+
+        | Placeholder | Value                                                           |
+        |-------------|-----------------------------------------------------------------|
+        | `{index}`   | The log index, as in the header unless a type is given          |
+        | `{level}`   | The level name, e.g. `INFO`                                     |
+        | `{symbol}`  | The level symbol, e.g. `*`                                      |
+        | `{time}`    | The wall-clock time; the spec is a `strftime`-style format      |
+        | `{elapsed}` | The timer, e.g. `1.250ms`; empty unless a timer is set          |
+        | `{target}`  | The module the message was logged from, if known                |
+        | `{msg}`     | The message, followed by its fields unless `{fields}` is used   |
+        | `{fields}`  | The key/value fields of the message                             |
+
+        Every placeholder but `{time}` takes a `[[fill]align][0][width][.precision]` spec;
+        `{index}` also takes a type of `x`, `X`, `o`, `b` or `d`, and the precision of
+        `{elapsed}` is its number of decimal places (3 by default). `NoColor` and `NoBold`
+        still apply, while the other header options are ignored. An invalid pattern is an
+        [InvalidInput](std::io::ErrorKind::InvalidInput) error.
+
+        The pattern applies to text output only; JSON lines always contain every field.
+     */
+    Pattern(&'a str),
+    /// Removes the pattern, restoring the header set by the other options (default).
+    NoPattern,
     /// Removes all extras; this is now just `eprintln!()`.
     Basic,
     /// Logs to the default file
     File,
     /// Logs to a specified file
     FileAt(&'a File),
-    /// Only logs to the file; requires `File` or `FileAt`.
+    /// Logs to the file at a path, replacing its contents; missing parent directories are created.
+    FilePath(&'a Path),
+    /// Logs to the end of the file at a path; missing parent directories are created.
+    FileAppend(&'a Path),
+    /// Only logs to the file; requires `File`, `FileAt`, `FilePath` or `FileAppend`.
     FileOnly,
+    /// Stops logging to the file, flushing and closing it; also undoes `FileOnly`.
+    NoFile,
     /// Include a timestamp in the log.
     Timer,
     /// Attach an existing timestamp to the log (to allow the use of a runtime timer within one's own program as the timer).
     TimerAt(&'a std::time::Instant),
-    /// Reset the logger's formatter to its default state.
+    /// Removes the timer.
+    NoTimer,
+    /// Include the wall-clock time in the log, in UTC or the local time zone, as RFC 3339; undoes `TimestampFormat`.
+    Timestamp(Clock),
+    /// Format the wall-clock time with a `strftime`-style format string instead of RFC 3339; implies `Timestamp`.
+    TimestampFormat(&'a str),
+    /// Removes the wall-clock time; the clock and its format are kept for `{time}` in patterns.
+    NoTimestamp,
+    /// Rotate the log file before it grows past the given number of bytes; requires `File`, `FilePath` or `FileAppend`.
+    MaxFileSize(u64),
+    /// Rotate the log file every hour or day; requires `File`, `FilePath` or `FileAppend`.
+    Rotate(Rotation),
+    /// Keep at most the given number of rotated log files (default 5); older ones are removed at the next rotation.
+    KeepFiles(usize),
+    /// Stops rotating the log file, by size or by time.
+    NoRotation,
+    /// Sets the console stream(s) written to instead of stderr, undoing `FileOnly`; `Stderr*` options apply to whichever is used.
+    Console(Stream),
+    /// Sets the output format for stderr.
+    StderrFormat(Format),
+    /// Sets the output format for the file.
+    FileFormat(Format),
+    /// Only log messages at or above the given level.
+    Level(LogLevel),
+    /// Only log messages at or above the given level to stderr.
+    StderrLevel(LogLevel),
+    /// Only log messages at or above the given level to the file.
+    FileLevel(LogLevel),
+    /// Sets what happens when a line cannot be written by a method other than a `try_` method (default [WriteFailure::Stderr]).
+    OnWriteFailure(WriteFailure),
+    /// Flushes the file and sinks after every message at or above the given level (default `Error`).
+    FlushLevel(LogLevel),
+    /// Flushes the file and sinks after a message if they were last flushed at least this long ago; nothing is flushed between messages.
+    FlushInterval(Duration),
+    /// Removes the flush interval (default).
+    NoFlushInterval,
+    /// Reset every setting to its default, closing the log file; the log index and sinks are kept.
     Reset,
 }
 
-#[derive(Clone, Copy)]
-enum LogLevel {
+/**
+    The severity level of a log message.
+
+    Levels are ordered from least to most severe, so they can be compared
+    against a logger's minimum level (see [Options::Level]).
+    `Trace` and `Debug` are below the default minimum level of `Info`, so
+    they are hidden unless enabled.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    /// Very verbose diagnostics; `.`.
+    Trace,
+    /// Diagnostics for debugging; `?`.
+    Debug,
+    /// General information; `*`.
     Info,
+    /// A successful operation; `+`.
+    Success,
+    /// Something may be wrong; `~`.
     Warn,
+    /// Something is wrong; `!`.
     Error,
-    Success,
+    /// Something is very wrong; `%`.
     Critical,
 }
+
+impl LogLevel {
+    /// The lowercase name of the level, e.g. `"info"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Trace => "trace",
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Success => "success",
+            LogLevel::Warn => "warn",
+            LogLevel::Error => "error",
+            LogLevel::Critical => "critical",
+        }
+    }
+}
+
+/**
+    The output format of a log line.
+
+    `Json` lines are single JSON objects, as in the [JSON Lines](https://jsonlines.org/) format:
+    ```json
+    {"index":0,"level":"info","message":"info","elapsed_ms":null,"timestamp":"2024-01-01T00:00:00.000000Z","fields":{"status":200}}
+    ```
+    `elapsed_ms` is `null` unless a timer is enabled, `timestamp` is an RFC 3339 timestamp in
+    the logger's [Clock], and `fields` holds any key/value fields.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// The bracketed text format.
+    #[default]
+    Text,
+    /// One JSON object per line.
+    Json,
+}
+
+/**
+    The radix the log index is written in.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Radix {
+    /// Lowercase hexadecimal, e.g. `00ff`.
+    #[default]
+    Hex,
+    /// Decimal, e.g. `0255`.
+    Decimal,
+}
+
+/**
+    Whether console output contains colour and bold escape sequences.
+
+    This is independent of `NoColor` and `NoBold`, which remove colours or bold
+    even when escape sequences are written. The log file and sinks never contain
+    escape sequences, and the `plain` feature disables them entirely.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorMode {
+    /**
+        Write escape sequences only if the console stream is a terminal.
+
+        This can be overridden by the environment: `CLICOLOR_FORCE` (other than `0`)
+        forces them on, and otherwise `NO_COLOR` or `CLICOLOR=0` turns them off.
+     */
+    #[default]
+    Auto,
+    /// Always write escape sequences.
+    Always,
+    /// Never write escape sequences.
+    Never,
+}
+
+impl ColorMode {
+    /// Whether to write escape sequences to a stream, given whether it is a terminal.
+    fn enabled(self, tty: bool) -> bool {
+        if cfg!(feature = "plain") {
+            return false;
+        }
+        match self {
+            ColorMode::Auto => env_color().unwrap_or(tty),
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+        }
+    }
+}
+
+/**
+    The console stream(s) that log lines are written to.
+
+    The `Stderr*` options, such as [Options::StderrLevel], apply to console
+    output on either stream.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Stream {
+    /// Write all lines to stderr.
+    #[default]
+    Stderr,
+    /// Write all lines to stdout.
+    Stdout,
+    /// Write `Trace`, `Debug`, `Info` and `Success` lines to stdout, and `Warn`,
+    /// `Error` and `Critical` lines to stderr.
+    Split,
+}
+
+impl Stream {
+    /// Whether a line at `lvl` is written to stdout rather than stderr.
+    fn is_stdout(self, lvl: LogLevel) -> bool {
+        match self {
+            Stream::Stderr => false,
+            Stream::Stdout => true,
+            Stream::Split => lvl < LogLevel::Warn,
+        }
+    }
+}
+
+/**
+    The time zone of wall-clock timestamps in the log header.
+
+    Timestamps are formatted as RFC 3339 with millisecond precision,
+    e.g. `2024-01-01T12:00:00.000Z` in UTC or `2024-01-01T13:00:00.000+01:00` in local time,
+    unless a custom format is set with [Options::TimestampFormat] after the last
+    [Options::Timestamp].
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Clock {
+    /// Coordinated Universal Time.
+    #[default]
+    Utc,
+    /// The local time zone of the system.
+    Local,
+}
+
+/**
+    How often the log file is rotated, independently of its size.
+
+    Periods are aligned to UTC, so `Daily` rotates at midnight UTC.
+    Rotated files are renamed `forestry.log.1`, `forestry.log.2`, and so on,
+    with `.1` being the most recent; see [Options::KeepFiles].
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rotation {
+    /// Never rotate on a schedule.
+    #[default]
+    Never,
+    /// Rotate at the start of every hour.
+    Hourly,
+    /// Rotate at the start of every day.
+    Daily,
+}
+
+impl Rotation {
+    /// The number of the period containing the current time, or 0 for `Never`.
+    fn current(self) -> u64 {
+        let secs = match self {
+            Rotation::Never => return 0,
+            Rotation::Hourly => 60 * 60,
+            Rotation::Daily => 24 * 60 * 60,
+        };
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() / secs)
+    }
+}
+
+/**
+    What the logging methods do when a line cannot be written to an output, e.g.
+    because stdout is a closed pipe or the disk is full.
+
+    The other outputs are written to regardless. The `try_` methods, such as
+    [try_info](Logger::try_info), return an [Error] instead.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WriteFailure {
+    /// Discard the line.
+    Ignore,
+    /// Write the error and the line to stderr instead, ignoring any failure to do so.
+    #[default]
+    Stderr,
+    /// Panic with the error.
+    Panic,
+}
+
+/**
+    An error parsing a setting, such as a [LogLevel], from a string.
+
+    Setting names are matched case-insensitively, ignoring surrounding whitespace.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The string that failed to parse.
+    pub value: String,
+    /// A description of the valid values.
+    pub expected: &'static str,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid value {:?}, expected {}", self.value, self.expected)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+macro_rules! impl_from_str {
+    ($t:ty, $expected:literal, { $($($s:literal)|+ => $v:expr),+ $(,)? }) => {
+        impl FromStr for $t {
+            type Err = ParseError;
+
+            fn from_str(s: &str) -> Result<Self, ParseError> {
+                match s.trim().to_ascii_lowercase().as_str() {
+                    $($($s)|+ => Ok($v),)+
+                    _ => Err(ParseError { value: s.to_string(), expected: $expected }),
+                }
+            }
+        }
+    };
+}
+
+impl_from_str!(LogLevel, "one of trace, debug, info, success, warn, error or critical", {
+    "trace" => LogLevel::Trace,
+    "debug" => LogLevel::Debug,
+    "info" => LogLevel::Info,
+    "success" => LogLevel::Success,
+    "warn" | "warning" => LogLevel::Warn,
+    "error" => LogLevel::Error,
+    "critical" => LogLevel::Critical,
+});
+
+impl_from_str!(Format, "one of text or json", {
+    "text" => Format::Text,
+    "json" => Format::Json,
+});
+
+impl_from_str!(Radix, "one of hex or decimal", {
+    "hex" => Radix::Hex,
+    "decimal" | "dec" => Radix::Decimal,
+});
+
+impl_from_str!(ColorMode, "one of auto, always or never", {
+    "auto" => ColorMode::Auto,
+    "always" | "true" | "on" => ColorMode::Always,
+    "never" | "false" | "off" => ColorMode::Never,
+});
+
+impl_from_str!(Stream, "one of stderr, stdout or split", {
+    "stderr" => Stream::Stderr,
+    "stdout" => Stream::Stdout,
+    "split" => Stream::Split,
+});
+
+impl_from_str!(Clock, "one of utc or local", {
+    "utc" => Clock::Utc,
+    "local" => Clock::Local,
+});
+
+impl_from_str!(Rotation, "one of never, hourly or daily", {
+    "never" => Rotation::Never,
+    "hourly" => Rotation::Hourly,
+    "daily" => Rotation::Daily,
+});
+
+impl_from_str!(Theme, "one of classic, colorblind or emoji", {
+    "classic" => Theme::classic(),
+    "colorblind" => Theme::colorblind(),
+    "emoji" => Theme::emoji(),
+});
+
+impl_from_str!(WriteFailure, "one of ignore, stderr or panic", {
+    "ignore" => WriteFailure::Ignore,
+    "stderr" => WriteFailure::Stderr,
+    "panic" => WriteFailure::Panic,
+});
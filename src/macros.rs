@@ -0,0 +1,124 @@
+//! Logging macros which write to the [global](crate::global) logger.
+//!
+//! Each macro takes the same arguments as [format!], and logs the result at its level.
+//! The message is only formatted if the global logger writes messages at that level.
+//! The macros are synchronous, including with the `async` feature.
+
+/**
+    Log a TRACE message with the global logger.
+
+    # Example
+    ```rust
+     forestry::trace!("{} + {} = {}", 1, 1, 2);
+    ```
+ */
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => {
+        if $crate::global::enabled($crate::logs::LogLevel::Trace) {
+            $crate::global::log_target($crate::logs::LogLevel::Trace, ::std::module_path!(), &::std::format!($($arg)+))
+        }
+    };
+}
+
+/**
+    Log a DEBUG message with the global logger.
+
+    # Example
+    ```rust
+     forestry::debug!("{} + {} = {}", 1, 1, 2);
+    ```
+ */
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        if $crate::global::enabled($crate::logs::LogLevel::Debug) {
+            $crate::global::log_target($crate::logs::LogLevel::Debug, ::std::module_path!(), &::std::format!($($arg)+))
+        }
+    };
+}
+
+/**
+    Log an INFO message with the global logger.
+
+    # Example
+    ```rust
+     forestry::info!("{} + {} = {}", 1, 1, 2);   // Output: [0000:*] 1 + 1 = 2
+    ```
+ */
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => {
+        if $crate::global::enabled($crate::logs::LogLevel::Info) {
+            $crate::global::log_target($crate::logs::LogLevel::Info, ::std::module_path!(), &::std::format!($($arg)+))
+        }
+    };
+}
+
+/**
+    Log a WARN message with the global logger.
+
+    # Example
+    ```rust
+     forestry::warn!("{} + {} = {}", 1, 1, 2);   // Output: [0000:~] 1 + 1 = 2
+    ```
+ */
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => {
+        if $crate::global::enabled($crate::logs::LogLevel::Warn) {
+            $crate::global::log_target($crate::logs::LogLevel::Warn, ::std::module_path!(), &::std::format!($($arg)+))
+        }
+    };
+}
+
+/**
+    Log an ERROR message with the global logger.
+
+    # Example
+    ```rust
+     forestry::error!("{} + {} = {}", 1, 1, 2);  // Output: [0000:!] 1 + 1 = 2
+    ```
+ */
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)+) => {
+        if $crate::global::enabled($crate::logs::LogLevel::Error) {
+            $crate::global::log_target($crate::logs::LogLevel::Error, ::std::module_path!(), &::std::format!($($arg)+))
+        }
+    };
+}
+
+/**
+    Log a SUCCESS message with the global logger.
+
+    # Example
+    ```rust
+     forestry::success!("{} + {} = {}", 1, 1, 2); // Output: [0000:+] 1 + 1 = 2
+    ```
+ */
+#[macro_export]
+macro_rules! success {
+    ($($arg:tt)+) => {
+        if $crate::global::enabled($crate::logs::LogLevel::Success) {
+            $crate::global::log_target($crate::logs::LogLevel::Success, ::std::module_path!(), &::std::format!($($arg)+))
+        }
+    };
+}
+
+/**
+    Log a CRITICAL message with the global logger.
+
+    # Example
+    ```rust
+     forestry::critical!("{} + {} = {}", 1, 1, 2); // Output: [0000:%] 1 + 1 = 2
+    ```
+ */
+#[macro_export]
+macro_rules! critical {
+    ($($arg:tt)+) => {
+        if $crate::global::enabled($crate::logs::LogLevel::Critical) {
+            $crate::global::log_target($crate::logs::LogLevel::Critical, ::std::module_path!(), &::std::format!($($arg)+))
+        }
+    };
+}
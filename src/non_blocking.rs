@@ -0,0 +1,390 @@
+//! Logging without waiting for output.
+//!
+//! [NonBlocking::new()] moves a [Logger] onto a worker thread and returns a handle which queues
+//! messages for it, so the calling thread never waits for stderr or the log file. The queue is
+//! bounded, and messages the worker has taken but not yet written still count against its
+//! capacity, so at most that many are held at once. When it is full, the [Overflow] policy
+//! decides whether to wait for room or to drop a message. The handle can be cloned and sent
+//! between threads, and all clones feed the same queue, in order.
+//!
+//! The worker thread runs until the [WorkerGuard] returned alongside the handle is dropped,
+//! which writes out every queued message and flushes the logger. Keep the guard alive, e.g. in
+//! `main`, for as long as messages are logged; messages logged after it is dropped are lost.
+//!
+//! The logger cannot be reconfigured once it is on the worker thread, so messages below the
+//! level of every output are discarded without being queued. Lines are formatted by the
+//! worker, so the timer and timestamps show when a line was written, which may be a little
+//! after it was logged.
+//!
+//! # Example
+//! ```rust
+//!  use forestry::prelude::*;
+//!  use forestry::non_blocking::NonBlocking;
+//!  let (log, _guard) = NonBlocking::new(Logger::new());
+//!  for i in 0..3 {
+//!      log.info(&format!("iteration {i}"));   // returns without writing
+//!  }
+//! ```
+
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+
+use crate::error::Error;
+use crate::kv::Value;
+use crate::logs::{LogLevel, Logger};
+
+/// The number of messages queued by [NonBlocking::new()] before the [Overflow] policy applies.
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+/**
+    What to do with a message when the queue is full.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overflow {
+    /// Wait until the worker makes room, so that no message is lost.
+    #[default]
+    Block,
+    /// Drop the new message.
+    DropNewest,
+    /**
+        Drop the oldest queued message to make room for the new one.
+
+        Messages the worker is already writing cannot be dropped, so if every message counted
+        against the capacity is being written, the new one is dropped instead.
+     */
+    DropOldest,
+}
+
+/// An item of work for the worker thread.
+enum Message {
+    Log {
+        lvl: LogLevel,
+        target: String,
+        msg: String,
+        kv: Vec<(String, Value<'static>)>,
+    },
+    Flush(Sender<Result<(), Error>>),
+}
+
+struct State {
+    messages: VecDeque<Message>,
+    /// The number of messages taken by the worker and not yet written.
+    writing: usize,
+    /// Whether the worker has stopped, or been asked to stop.
+    closed: bool,
+}
+
+impl State {
+    /// The number of messages counted against the capacity, including those being written.
+    fn len(&self) -> usize {
+        self.messages.len() + self.writing
+    }
+}
+
+struct Queue {
+    state: Mutex<State>,
+    /// Signalled when messages are queued or the queue is closed.
+    ready: Condvar,
+    /// Signalled when the worker has written the messages it took off the queue.
+    room: Condvar,
+    capacity: usize,
+    overflow: Overflow,
+    dropped: AtomicU64,
+}
+
+impl Queue {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Close the queue and wake every waiting thread.
+    fn close(&self) {
+        self.lock().closed = true;
+        self.ready.notify_all();
+        self.room.notify_all();
+    }
+}
+
+/**
+    Closes the queue when the worker exits, even by panicking, so that senders do not block,
+    and discards anything left in it, so that callers of [NonBlocking::flush] stop waiting.
+ */
+struct CloseOnExit(Arc<Queue>);
+
+impl Drop for CloseOnExit {
+    fn drop(&mut self) {
+        self.0.close();
+        self.0.lock().messages.clear();
+    }
+}
+
+/// A view of an owned field value which borrows its string.
+fn borrowed<'a>(v: &'a Value<'static>) -> Value<'a> {
+    match v {
+        Value::Str(s) => Value::Str(Cow::Borrowed(s)),
+        v => v.clone(),
+    }
+}
+
+fn work(queue: Arc<Queue>, mut logger: Logger) {
+    let _close = CloseOnExit(queue.clone());
+    loop {
+        let batch = {
+            let mut state = queue.lock();
+            while state.messages.is_empty() && !state.closed {
+                state = queue.ready.wait(state).unwrap_or_else(|e| e.into_inner());
+            }
+            if state.messages.is_empty() {
+                break;
+            }
+            state.writing = state.messages.len();
+            std::mem::take(&mut state.messages)
+        };
+        for message in batch {
+            match message {
+                Message::Log { lvl, target, msg, kv } => {
+                    let kv: Vec<(&str, Value<'_>)> = kv.iter().map(|(k, v)| (k.as_str(), borrowed(v))).collect();
+                    logger.print_target(lvl, &target, &msg, &kv);
+                },
+                Message::Flush(done) => {
+                    let _ = done.send(logger.flush().map(|_| ()));
+                },
+            }
+        }
+        queue.lock().writing = 0;
+        queue.room.notify_all();
+    }
+}
+
+/**
+    A cloneable, `Send + Sync` handle which queues messages for a [Logger] on a worker thread.
+
+    See the [module docs](self).
+ */
+#[derive(Clone)]
+pub struct NonBlocking {
+    queue: Arc<Queue>,
+    /// The lowest level which goes to any output, if any does; other messages are not queued.
+    min_level: Option<LogLevel>,
+}
+
+/**
+    Keeps the worker thread of a [NonBlocking] handle running.
+
+    Dropping it writes out every queued message, flushes the logger and waits for the
+    worker thread to finish.
+ */
+pub struct WorkerGuard {
+    queue: Arc<Queue>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl Drop for WorkerGuard {
+    fn drop(&mut self) {
+        self.queue.close();
+        if let Some(w) = self.worker.take() {
+            let _ = w.join();
+        }
+    }
+}
+
+impl NonBlocking {
+    /**
+        Move `logger` onto a worker thread, with a queue of [DEFAULT_CAPACITY] messages
+        which blocks when full.
+     */
+    pub fn new(logger: Logger) -> (Self, WorkerGuard) {
+        Self::with_capacity(logger, DEFAULT_CAPACITY, Overflow::Block)
+    }
+
+    /**
+        Move `logger` onto a worker thread, with a queue of `capacity` messages and the given
+        policy for when it is full.
+
+        # Panics
+        If `capacity` is 0.
+     */
+    pub fn with_capacity(logger: Logger, capacity: usize, overflow: Overflow) -> (Self, WorkerGuard) {
+        assert!(capacity > 0, "a non-blocking logger needs room for at least one message");
+        let min_level = [
+            LogLevel::Trace,
+            LogLevel::Debug,
+            LogLevel::Info,
+            LogLevel::Success,
+            LogLevel::Warn,
+            LogLevel::Error,
+            LogLevel::Critical,
+        ]
+            .into_iter()
+            .find(|&lvl| logger.enabled(lvl));
+        let queue = Arc::new(Queue {
+            state: Mutex::new(State { messages: VecDeque::new(), writing: 0, closed: false }),
+            ready: Condvar::new(),
+            room: Condvar::new(),
+            capacity,
+            overflow,
+            dropped: AtomicU64::new(0),
+        });
+        let worker = {
+            let queue = queue.clone();
+            thread::Builder::new()
+                .name("forestry".into())
+                .spawn(move || work(queue, logger))
+                .expect("failed to spawn the logging thread")
+        };
+        (NonBlocking { queue: queue.clone(), min_level }, WorkerGuard { queue, worker: Some(worker) })
+    }
+
+    /// The number of messages dropped because the queue was full or the worker had stopped.
+    pub fn dropped(&self) -> u64 {
+        self.queue.dropped.load(Ordering::Relaxed)
+    }
+
+    /**
+        Wait until every message queued so far has been written, then flush the logger;
+        see [Logger::flush].
+
+        If the worker has stopped, this returns immediately.
+     */
+    pub fn flush(&self) -> Result<&Self, Error> {
+        let (done, wait) = mpsc::channel();
+        {
+            let mut state = self.queue.lock();
+            if state.closed {
+                return Ok(self);
+            }
+            state.messages.push_back(Message::Flush(done));
+        }
+        self.queue.ready.notify_one();
+        match wait.recv() {
+            Ok(result) => result.map(|()| self),
+            Err(_) => Ok(self),
+        }
+    }
+
+    /// Log a TRACE message; see [Logger::trace].
+    pub fn trace(&self, s: &str) -> &Self {
+        self.print(LogLevel::Trace, s, &[])
+    }
+
+    /// Log a DEBUG message; see [Logger::debug].
+    pub fn debug(&self, s: &str) -> &Self {
+        self.print(LogLevel::Debug, s, &[])
+    }
+
+    /// Log an INFO message; see [Logger::info].
+    pub fn info(&self, s: &str) -> &Self {
+        self.print(LogLevel::Info, s, &[])
+    }
+
+    /// Log a WARN message; see [Logger::warn].
+    pub fn warn(&self, s: &str) -> &Self {
+        self.print(LogLevel::Warn, s, &[])
+    }
+
+    /// Log an ERROR message; see [Logger::error].
+    pub fn error(&self, s: &str) -> &Self {
+        self.print(LogLevel::Error, s, &[])
+    }
+
+    /// Log a SUCCESS message; see [Logger::success].
+    pub fn success(&self, s: &str) -> &Self {
+        self.print(LogLevel::Success, s, &[])
+    }
+
+    /// Log a CRITICAL message; see [Logger::critical].
+    pub fn critical(&self, s: &str) -> &Self {
+        self.print(LogLevel::Critical, s, &[])
+    }
+
+    /// Log a message at the given level; see [Logger::log].
+    pub fn log(&self, lvl: LogLevel, s: &str) -> &Self {
+        self.print(lvl, s, &[])
+    }
+
+    /// Log a message with key/value fields at the given level; see [Logger::log_kv].
+    pub fn log_kv(&self, lvl: LogLevel, s: &str, kv: &[(&str, Value<'_>)]) -> &Self {
+        self.print(lvl, s, kv)
+    }
+
+    /// Log a TRACE message with key/value fields; see [Logger::trace_kv].
+    pub fn trace_kv(&self, s: &str, kv: &[(&str, Value<'_>)]) -> &Self {
+        self.print(LogLevel::Trace, s, kv)
+    }
+
+    /// Log a DEBUG message with key/value fields; see [Logger::debug_kv].
+    pub fn debug_kv(&self, s: &str, kv: &[(&str, Value<'_>)]) -> &Self {
+        self.print(LogLevel::Debug, s, kv)
+    }
+
+    /// Log an INFO message with key/value fields; see [Logger::info_kv].
+    pub fn info_kv(&self, s: &str, kv: &[(&str, Value<'_>)]) -> &Self {
+        self.print(LogLevel::Info, s, kv)
+    }
+
+    /// Log a WARN message with key/value fields; see [Logger::warn_kv].
+    pub fn warn_kv(&self, s: &str, kv: &[(&str, Value<'_>)]) -> &Self {
+        self.print(LogLevel::Warn, s, kv)
+    }
+
+    /// Log an ERROR message with key/value fields; see [Logger::error_kv].
+    pub fn error_kv(&self, s: &str, kv: &[(&str, Value<'_>)]) -> &Self {
+        self.print(LogLevel::Error, s, kv)
+    }
+
+    /// Log a SUCCESS message with key/value fields; see [Logger::success_kv].
+    pub fn success_kv(&self, s: &str, kv: &[(&str, Value<'_>)]) -> &Self {
+        self.print(LogLevel::Success, s, kv)
+    }
+
+    /// Log a CRITICAL message with key/value fields; see [Logger::critical_kv].
+    pub fn critical_kv(&self, s: &str, kv: &[(&str, Value<'_>)]) -> &Self {
+        self.print(LogLevel::Critical, s, kv)
+    }
+
+    pub(crate) fn print(&self, lvl: LogLevel, s: &str, kv: &[(&str, Value<'_>)]) -> &Self {
+        self.print_target(lvl, "", s, kv)
+    }
+
+    /// Queue a message from the given target, applying the overflow policy if the queue is full.
+    pub(crate) fn print_target(&self, lvl: LogLevel, target: &str, s: &str, kv: &[(&str, Value<'_>)]) -> &Self {
+        if self.min_level.is_none_or(|min| lvl < min) {
+            return self;
+        }
+        let queue = &*self.queue;
+        let mut state = queue.lock();
+        if queue.overflow == Overflow::Block {
+            while state.len() >= queue.capacity && !state.closed {
+                state = queue.room.wait(state).unwrap_or_else(|e| e.into_inner());
+            }
+        }
+        if state.closed {
+            queue.dropped.fetch_add(1, Ordering::Relaxed);
+            return self;
+        }
+        if state.len() >= queue.capacity {
+            queue.dropped.fetch_add(1, Ordering::Relaxed);
+            let oldest = match queue.overflow {
+                Overflow::DropOldest => state.messages.iter().position(|m| matches!(m, Message::Log { .. })),
+                _ => None,
+            };
+            match oldest {
+                Some(i) => { state.messages.remove(i); },
+                None => return self,
+            }
+        }
+        state.messages.push_back(Message::Log {
+            lvl,
+            target: target.to_string(),
+            msg: s.to_string(),
+            kv: kv.iter().map(|(k, v)| (k.to_string(), v.clone().into_owned())).collect(),
+        });
+        drop(state);
+        queue.ready.notify_one();
+        self
+    }
+}
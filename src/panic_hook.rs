@@ -0,0 +1,67 @@
+//! Logging panics through a logger.
+
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::panic::{self, PanicHookInfo};
+use std::time::Duration;
+
+use crate::logs::LogLevel;
+use crate::shared::SharedLogger;
+
+/// How long the hook waits for another thread to release the logger.
+const LOCK_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// The message a panic was raised with.
+fn payload<'a>(info: &'a PanicHookInfo<'_>) -> &'a str {
+    let payload = info.payload();
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s
+    } else {
+        "Box<dyn Any>"
+    }
+}
+
+/**
+    Log every panic as a CRITICAL message through `logger`.
+
+    The message gives the panicking thread and the panic's location and message, like that
+    of the default hook. A backtrace follows it if `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE`
+    enables one. The log file and sinks are then flushed,
+    and the previously installed hook, which by default prints the panic to stderr, is called.
+
+    Write failures are ignored, as a panic within a panic hook aborts the process. If the
+    logger is still locked after a short wait, for example because the panic happened while
+    it was writing, the panic is not logged.
+
+    # Example
+    ```rust
+     use forestry::prelude::*;
+     let log = SharedLogger::new(Logger::new());
+     forestry::install_panic_hook(log.clone());
+     // panic!("boom");   // Output: [0000:%] thread 'main' panicked at src/main.rs:4:2: boom
+     # let _ = std::panic::take_hook();
+    ```
+ */
+pub fn install_panic_hook(logger: impl Into<SharedLogger>) {
+    let logger = logger.into();
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let thread = std::thread::current();
+        let name = thread.name().unwrap_or("<unnamed>");
+        let mut msg = match info.location() {
+            Some(l) => format!("thread '{}' panicked at {}: {}", name, l, payload(info)),
+            None => format!("thread '{}' panicked: {}", name, payload(info)),
+        };
+        let backtrace = Backtrace::capture();
+        if backtrace.status() == BacktraceStatus::Captured {
+            msg.push_str(&format!("\nstack backtrace:\n{}", backtrace));
+        }
+        if let Some(mut log) = logger.try_lock_for(LOCK_TIMEOUT) {
+            let _ = log.try_log(LogLevel::Critical, &msg);
+            let _ = log.flush();
+            logger.sync_index(&log);
+        }
+        previous(info);
+    }));
+}
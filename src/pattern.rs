@@ -0,0 +1,283 @@
+//! Parsing of the line patterns set with [Options::Pattern](crate::logs::Options::Pattern).
+//!
+//! A pattern is literal text with `{name}` or `{name:spec}` placeholders, much like a
+//! [format!] string; `{{` and `}}` are literal braces. The spec is the usual
+//! `[[fill]align][0][width][.precision][type]`, except for `{time}`, whose spec is a
+//! `strftime`-style format string.
+
+use std::io;
+
+/// A part of a log line which a placeholder stands for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Field {
+    /// The log index.
+    Index,
+    /// The level name, e.g. `INFO`.
+    Level,
+    /// The level symbol from the theme.
+    Symbol,
+    /// The wall-clock time, with an optional `strftime` format.
+    Time(Option<String>),
+    /// The time elapsed since the timer started.
+    Elapsed,
+    /// The module or target the message was logged from.
+    Target,
+    /// The message, followed by its fields unless the pattern has `{fields}`.
+    Msg,
+    /// The key/value fields of the message.
+    Fields,
+}
+
+/// How a placeholder is aligned within its width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// The width, alignment, padding, precision and type of a placeholder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Spec {
+    pub fill: char,
+    pub align: Option<Align>,
+    pub zero: bool,
+    pub width: usize,
+    pub precision: Option<usize>,
+    pub ty: Option<char>,
+}
+
+impl Default for Spec {
+    fn default() -> Self {
+        Spec {
+            fill: ' ',
+            align: None,
+            zero: false,
+            width: 0,
+            precision: None,
+            ty: None,
+        }
+    }
+}
+
+impl Spec {
+    /**
+        Pad `s` to the spec's width.
+
+        Text is left-aligned by default and numbers right-aligned; the `0` flag pads
+        numbers with zeroes.
+     */
+    pub(crate) fn pad(&self, s: &str, numeric: bool) -> String {
+        let len = s.chars().count();
+        if len >= self.width {
+            return s.to_string();
+        }
+        let n = self.width - len;
+        let (fill, align) = match self.align {
+            Some(a) => (self.fill, a),
+            None if numeric && self.zero => ('0', Align::Right),
+            None if numeric => (' ', Align::Right),
+            None => (' ', Align::Left),
+        };
+        let (before, after) = match align {
+            Align::Left => (0, n),
+            Align::Center => (n / 2, n - n / 2),
+            Align::Right => (n, 0),
+        };
+        let mut out = String::with_capacity(s.len() + n);
+        out.extend(std::iter::repeat_n(fill, before));
+        out.push_str(s);
+        out.extend(std::iter::repeat_n(fill, after));
+        out
+    }
+
+    /// Format the log index with the spec's type, or as `default` without one, then pad it.
+    pub(crate) fn index(&self, index: u64, default: String) -> String {
+        let s = match self.ty {
+            None => default,
+            Some('x') => format!("{:x}", index),
+            Some('X') => format!("{:X}", index),
+            Some('o') => format!("{:o}", index),
+            Some('b') => format!("{:b}", index),
+            _ => index.to_string(),
+        };
+        self.pad(&s, true)
+    }
+
+    /// Truncate `s` to the spec's precision, then pad it.
+    pub(crate) fn text(&self, s: &str) -> String {
+        match self.precision {
+            Some(p) => self.pad(&s.chars().take(p).collect::<String>(), false),
+            None => self.pad(s, false),
+        }
+    }
+}
+
+/// A piece of a pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Piece {
+    Literal(String),
+    Field(Field, Spec),
+}
+
+/// A parsed line pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Pattern {
+    /// The pattern string, as given.
+    pub source: String,
+    pub pieces: Vec<Piece>,
+    /// Whether the pattern has a `{fields}` placeholder.
+    pub fields: bool,
+}
+
+fn invalid(pattern: &str, why: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("invalid pattern {:?}: {}", pattern, why),
+    )
+}
+
+impl Pattern {
+    /// Parse a pattern string.
+    pub(crate) fn parse(pattern: &str) -> Result<Self, io::Error> {
+        let mut pieces = Vec::new();
+        let mut lit = String::new();
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    lit.push('{');
+                },
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    lit.push('}');
+                },
+                '}' => return Err(invalid(pattern, "unmatched `}`")),
+                '{' => {
+                    let mut inner = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => inner.push(c),
+                            None => return Err(invalid(pattern, "unclosed `{`")),
+                        }
+                    }
+                    if !lit.is_empty() {
+                        pieces.push(Piece::Literal(std::mem::take(&mut lit)));
+                    }
+                    pieces.push(Self::placeholder(pattern, &inner)?);
+                },
+                c => lit.push(c),
+            }
+        }
+        if !lit.is_empty() {
+            pieces.push(Piece::Literal(lit));
+        }
+        let fields = pieces.iter().any(|p| matches!(p, Piece::Field(Field::Fields, _)));
+        Ok(Pattern { source: pattern.to_string(), pieces, fields })
+    }
+
+    fn placeholder(pattern: &str, inner: &str) -> Result<Piece, io::Error> {
+        let (name, spec) = match inner.split_once(':') {
+            Some((name, spec)) => (name, Some(spec)),
+            None => (inner, None),
+        };
+        if name == "time" {
+            if let Some(f) = spec {
+                crate::logs::check_clock_format(f)?;
+            }
+            return Ok(Piece::Field(Field::Time(spec.map(String::from)), Spec::default()));
+        }
+        let field = match name {
+            "index" => Field::Index,
+            "level" => Field::Level,
+            "symbol" => Field::Symbol,
+            "elapsed" => Field::Elapsed,
+            "target" => Field::Target,
+            "msg" => Field::Msg,
+            "fields" => Field::Fields,
+            _ => return Err(invalid(pattern, &format!("unknown placeholder `{{{}}}`", name))),
+        };
+        let spec = match spec {
+            Some(s) => Self::spec(pattern, s)?,
+            None => Spec::default(),
+        };
+        if spec.ty.is_some() && field != Field::Index {
+            return Err(invalid(pattern, &format!("`{{{}}}` does not take a type", name)));
+        }
+        Ok(Piece::Field(field, spec))
+    }
+
+    fn spec(pattern: &str, s: &str) -> Result<Spec, io::Error> {
+        let align = |c| match c {
+            '<' => Some(Align::Left),
+            '^' => Some(Align::Center),
+            '>' => Some(Align::Right),
+            _ => None,
+        };
+        let mut spec = Spec::default();
+        let chars: Vec<char> = s.chars().collect();
+        let mut i = 0;
+        if chars.len() >= 2 && align(chars[1]).is_some() {
+            spec.fill = chars[0];
+            spec.align = align(chars[1]);
+            i = 2;
+        } else if let Some(a) = chars.first().and_then(|&c| align(c)) {
+            spec.align = Some(a);
+            i = 1;
+        }
+        if chars.get(i) == Some(&'0') {
+            spec.zero = true;
+            i += 1;
+        }
+        let digits = |i: &mut usize| {
+            let start = *i;
+            while chars.get(*i).is_some_and(|c| c.is_ascii_digit()) {
+                *i += 1;
+            }
+            chars[start..*i].iter().collect::<String>().parse::<usize>().ok()
+        };
+        spec.width = digits(&mut i).unwrap_or(0);
+        if chars.get(i) == Some(&'.') {
+            i += 1;
+            spec.precision = Some(digits(&mut i).ok_or_else(|| invalid(pattern, "missing precision"))?);
+        }
+        if let Some(&c) = chars.get(i) {
+            if !"xXobd".contains(c) {
+                return Err(invalid(pattern, &format!("unknown format spec {:?}", s)));
+            }
+            spec.ty = Some(c);
+            i += 1;
+        }
+        if i != chars.len() {
+            return Err(invalid(pattern, &format!("unknown format spec {:?}", s)));
+        }
+        Ok(spec)
+    }
+
+    /// The pattern of the classic `[index:symbol](elapsed) message` line, for the given flags.
+    pub(crate) fn from_flags(flags: u8) -> Self {
+        let field = |f| Piece::Field(f, Spec::default());
+        let lit = |s: &str| Piece::Literal(s.to_string());
+        let mut pieces = Vec::new();
+        if flags & 0b10000000 != 0 {
+            pieces.push(field(Field::Time(None)));
+            pieces.push(lit(" "));
+        }
+        match flags & 0b0011 {
+            0b00 => pieces.extend([lit("["), field(Field::Index), lit(":"), field(Field::Symbol), lit("]")]),
+            0b10 => pieces.extend([lit("["), field(Field::Index), lit("]")]),
+            0b01 => pieces.extend([lit("["), field(Field::Symbol), lit("]")]),
+            _ => {},
+        }
+        if flags & 0b01000000 != 0 {
+            pieces.extend([lit("("), field(Field::Elapsed), lit(")")]);
+        }
+        if !pieces.is_empty() {
+            pieces.push(lit(" "));
+        }
+        pieces.push(field(Field::Msg));
+        Pattern { source: String::new(), pieces, fields: false }
+    }
+}
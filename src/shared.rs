@@ -0,0 +1,269 @@
+//! A thread-safe handle to a [Logger].
+//!
+//! [SharedLogger] is cheap to clone and can be sent to and shared between threads. All clones
+//! write through the same [Logger], so they share one log index, and each message is written
+//! whole before the next one starts, so lines from different threads never interleave.
+//!
+//! # Example
+//! ```rust
+//!  use forestry::prelude::*;
+//!  let log = SharedLogger::new(Logger::new());
+//!  let handles: Vec<_> = (0..4).map(|i| {
+//!      let log = log.clone();
+//!      std::thread::spawn(move || {
+//!          log.info(&format!("worker {i}"));
+//!      })
+//!  }).collect();
+//!  for h in handles {
+//!      h.join().unwrap();
+//!  }
+//!  assert_eq!(log.index(), 4);
+//! ```
+
+use std::io;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+use std::time::{Duration, Instant};
+
+use crate::kv::Value;
+use crate::error::Error;
+use crate::logs::{LogLevel, Logger, Options};
+
+struct Inner {
+    index: AtomicU64,
+    logger: Mutex<Logger>,
+}
+
+/**
+    A cloneable, `Send + Sync` handle to a [Logger].
+
+    Writes are serialised through a mutex around the logger, and the log index
+    is mirrored in an atomic counter so it can be read without locking.
+ */
+#[derive(Clone)]
+pub struct SharedLogger {
+    inner: Arc<Inner>,
+}
+
+impl SharedLogger {
+    /**
+        Share a configured logger.
+
+        The shared index continues from the logger's current index.
+     */
+    pub fn new(logger: Logger) -> Self {
+        SharedLogger {
+            inner: Arc::new(Inner {
+                index: AtomicU64::new(logger.index()),
+                logger: Mutex::new(logger),
+            }),
+        }
+    }
+
+    /**
+        Lock the underlying logger.
+
+        This can be used to change its configuration at runtime. Other threads
+        block on logging until the guard is dropped, which also updates the shared
+        log index.
+     */
+    pub fn lock(&self) -> SharedLoggerGuard<'_> {
+        SharedLoggerGuard {
+            logger: self.inner.logger.lock().unwrap_or_else(|e| e.into_inner()),
+            index: &self.inner.index,
+        }
+    }
+
+    /// Lock the underlying logger, or give up if other threads hold it for longer than `timeout`.
+    pub(crate) fn try_lock_for(&self, timeout: Duration) -> Option<MutexGuard<'_, Logger>> {
+        let start = Instant::now();
+        loop {
+            match self.inner.logger.try_lock() {
+                Ok(logger) => return Some(logger),
+                Err(TryLockError::Poisoned(e)) => return Some(e.into_inner()),
+                Err(TryLockError::WouldBlock) if start.elapsed() < timeout => std::thread::yield_now(),
+                Err(TryLockError::WouldBlock) => return None,
+            }
+        }
+    }
+
+    /// Mirror the index of the locked logger in the atomic counter.
+    pub(crate) fn sync_index(&self, logger: &Logger) {
+        self.inner.index.store(logger.index(), Ordering::Release);
+    }
+
+    /**
+        Configure the underlying logger with options.
+
+        See [Options] for more details.
+     */
+    pub fn cfg(&self, opts: &[Options]) -> Result<&Self, io::Error> {
+        self.lock().cfg(opts)?;
+        Ok(self)
+    }
+
+    /// Whether a message at `lvl` would be written to any output.
+    pub(crate) fn enabled(&self, lvl: LogLevel) -> bool {
+        self.lock().enabled(lvl)
+    }
+
+    /// Get the index of the next message.
+    pub fn index(&self) -> u64 {
+        self.inner.index.load(Ordering::Acquire)
+    }
+
+    /// Set the index of the next message; see [Logger::set_index].
+    pub fn set_index(&self, index: u64) -> &Self {
+        self.lock().set_index(index);
+        self
+    }
+
+    /// Reset the index of the next message to 0; see [Logger::reset_index].
+    pub fn reset_index(&self) -> &Self {
+        self.set_index(0)
+    }
+
+    /// Write out any buffered output to the log file and sinks; see [Logger::flush].
+    pub fn flush(&self) -> Result<&Self, Error> {
+        self.lock().flush()?;
+        Ok(self)
+    }
+
+    /// Log a TRACE message; see [Logger::trace].
+    pub fn trace(&self, s: &str) -> &Self {
+        self.print(LogLevel::Trace, s, &[])
+    }
+
+    /// Log a DEBUG message; see [Logger::debug].
+    pub fn debug(&self, s: &str) -> &Self {
+        self.print(LogLevel::Debug, s, &[])
+    }
+
+    /// Log an INFO message; see [Logger::info].
+    pub fn info(&self, s: &str) -> &Self {
+        self.print(LogLevel::Info, s, &[])
+    }
+
+    /// Log a WARN message; see [Logger::warn].
+    pub fn warn(&self, s: &str) -> &Self {
+        self.print(LogLevel::Warn, s, &[])
+    }
+
+    /// Log an ERROR message; see [Logger::error].
+    pub fn error(&self, s: &str) -> &Self {
+        self.print(LogLevel::Error, s, &[])
+    }
+
+    /// Log a SUCCESS message; see [Logger::success].
+    pub fn success(&self, s: &str) -> &Self {
+        self.print(LogLevel::Success, s, &[])
+    }
+
+    /// Log a CRITICAL message; see [Logger::critical].
+    pub fn critical(&self, s: &str) -> &Self {
+        self.print(LogLevel::Critical, s, &[])
+    }
+
+    /// Log a message at the given level; see [Logger::log].
+    pub fn log(&self, lvl: LogLevel, s: &str) -> &Self {
+        self.print(lvl, s, &[])
+    }
+
+    /// Log a message with key/value fields at the given level; see [Logger::log_kv].
+    pub fn log_kv(&self, lvl: LogLevel, s: &str, kv: &[(&str, Value<'_>)]) -> &Self {
+        self.print(lvl, s, kv)
+    }
+
+    /// Log a message at the given level, or return the first write which failed; see [Logger::try_log].
+    pub fn try_log(&self, lvl: LogLevel, s: &str) -> Result<&Self, Error> {
+        self.try_log_kv(lvl, s, &[])
+    }
+
+    /// Log a message with key/value fields at the given level, or return the first write which failed; see [Logger::try_log_kv].
+    pub fn try_log_kv(&self, lvl: LogLevel, s: &str, kv: &[(&str, Value<'_>)]) -> Result<&Self, Error> {
+        self.lock().try_log_kv(lvl, s, kv)?;
+        Ok(self)
+    }
+
+    /// Log a TRACE message with key/value fields; see [Logger::trace_kv].
+    pub fn trace_kv(&self, s: &str, kv: &[(&str, Value<'_>)]) -> &Self {
+        self.print(LogLevel::Trace, s, kv)
+    }
+
+    /// Log a DEBUG message with key/value fields; see [Logger::debug_kv].
+    pub fn debug_kv(&self, s: &str, kv: &[(&str, Value<'_>)]) -> &Self {
+        self.print(LogLevel::Debug, s, kv)
+    }
+
+    /// Log an INFO message with key/value fields; see [Logger::info_kv].
+    pub fn info_kv(&self, s: &str, kv: &[(&str, Value<'_>)]) -> &Self {
+        self.print(LogLevel::Info, s, kv)
+    }
+
+    /// Log a WARN message with key/value fields; see [Logger::warn_kv].
+    pub fn warn_kv(&self, s: &str, kv: &[(&str, Value<'_>)]) -> &Self {
+        self.print(LogLevel::Warn, s, kv)
+    }
+
+    /// Log an ERROR message with key/value fields; see [Logger::error_kv].
+    pub fn error_kv(&self, s: &str, kv: &[(&str, Value<'_>)]) -> &Self {
+        self.print(LogLevel::Error, s, kv)
+    }
+
+    /// Log a SUCCESS message with key/value fields; see [Logger::success_kv].
+    pub fn success_kv(&self, s: &str, kv: &[(&str, Value<'_>)]) -> &Self {
+        self.print(LogLevel::Success, s, kv)
+    }
+
+    /// Log a CRITICAL message with key/value fields; see [Logger::critical_kv].
+    pub fn critical_kv(&self, s: &str, kv: &[(&str, Value<'_>)]) -> &Self {
+        self.print(LogLevel::Critical, s, kv)
+    }
+
+    pub(crate) fn print(&self, lvl: LogLevel, s: &str, kv: &[(&str, Value<'_>)]) -> &Self {
+        self.print_target(lvl, "", s, kv)
+    }
+
+    pub(crate) fn print_target(&self, lvl: LogLevel, target: &str, s: &str, kv: &[(&str, Value<'_>)]) -> &Self {
+        self.lock().print_target(lvl, target, s, kv);
+        self
+    }
+}
+
+/**
+    A lock on the [Logger] of a [SharedLogger], returned by [SharedLogger::lock].
+
+    Dropping it releases the lock and mirrors the logger's index in the shared counter, so
+    that messages logged and indexes set through it are seen by [SharedLogger::index].
+ */
+pub struct SharedLoggerGuard<'a> {
+    logger: MutexGuard<'a, Logger>,
+    index: &'a AtomicU64,
+}
+
+impl Deref for SharedLoggerGuard<'_> {
+    type Target = Logger;
+
+    fn deref(&self) -> &Logger {
+        &self.logger
+    }
+}
+
+impl DerefMut for SharedLoggerGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Logger {
+        &mut self.logger
+    }
+}
+
+impl Drop for SharedLoggerGuard<'_> {
+    fn drop(&mut self) {
+        self.index.store(self.logger.index(), Ordering::Release);
+    }
+}
+
+impl From<Logger> for SharedLogger {
+    fn from(logger: Logger) -> Self {
+        Self::new(logger)
+    }
+}
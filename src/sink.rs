@@ -0,0 +1,158 @@
+//! Pluggable outputs for a [Logger](crate::logs::Logger).
+//!
+//! Besides stderr and its log file, a logger can write to any number of [Sink]s, each with its
+//! own minimum [LogLevel] and [Format](crate::logs::Format); see
+//! [add_sink](crate::logs::Logger::add_sink). Text lines written to sinks are always plain,
+//! without colour or bold.
+//!
+//! [Sink] is implemented for common writers such as [`Vec<u8>`](Vec), [io::Stdout] and [File], and
+//! any other [io::Write] type can be used by wrapping it in a [WriteSink].
+//! A sink behind an `Arc<Mutex<_>>` is also a sink, so its output can be read back while the
+//! logger still holds it.
+//! Its methods are named so as not to clash with those of [io::Write].
+//!
+//! Sinks are always written to synchronously, including with the `async` feature.
+//!
+//! # Example
+//! ```rust
+//!  use forestry::prelude::*;
+//!  use std::sync::{Arc, Mutex};
+//!  let buf = Arc::new(Mutex::new(Vec::new()));
+//!  let mut log = Logger::new();
+//!  log.add_sink(buf.clone(), LogLevel::Warn, Format::Text);
+//!  log.info("info")
+//!      .warn("warning");
+//!  assert_eq!(buf.lock().unwrap().as_slice(), b"[0001:~] warning\n");
+//! ```
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::net::TcpStream;
+use std::sync::{Arc, Mutex};
+
+use crate::kv::Value;
+use crate::logs::LogLevel;
+
+/**
+    A log message, as passed to a [Sink] alongside its formatted line.
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a> {
+    /// The index of the message.
+    pub index: u64,
+    /// The level of the message.
+    pub level: LogLevel,
+    /// The module or target the message was logged from, or `""` if unknown.
+    pub target: &'a str,
+    /// The message itself, without header or fields.
+    pub message: &'a str,
+    /// The key/value fields attached to the message.
+    pub fields: &'a [(&'a str, Value<'a>)],
+}
+
+/**
+    A destination for log lines.
+
+    # Example
+    ```rust
+     use forestry::prelude::*;
+     use std::io;
+
+     /// Counts the errors logged.
+     struct ErrorCount(usize);
+
+     impl Sink for ErrorCount {
+         fn write_line(&mut self, record: &Record, _line: &str) -> io::Result<()> {
+             if record.level >= LogLevel::Error {
+                 self.0 += 1;
+             }
+             Ok(())
+         }
+     }
+    ```
+ */
+pub trait Sink: Send {
+    /**
+        Write a log line.
+
+        `line` is the fully formatted line, including its trailing newline.
+     */
+    fn write_line(&mut self, record: &Record<'_>, line: &str) -> io::Result<()>;
+
+    /// Flush any buffered output.
+    fn flush_sink(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/**
+    A [Sink] which writes lines to any [io::Write] type.
+
+    # Example
+    ```rust
+     use forestry::prelude::*;
+     let mut log = Logger::new();
+     let socket = std::io::sink(); // e.g. a `UnixStream`
+     log.add_sink(WriteSink(socket), LogLevel::Info, Format::Json);
+    ```
+ */
+#[derive(Debug)]
+pub struct WriteSink<W: Write + Send>(pub W);
+
+impl<W: Write + Send> Sink for WriteSink<W> {
+    fn write_line(&mut self, _: &Record<'_>, line: &str) -> io::Result<()> {
+        self.0.write_all(line.as_bytes())
+    }
+
+    fn flush_sink(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+macro_rules! impl_sink_for_writer {
+    ($($t:ty),+) => {
+        $(
+            impl Sink for $t {
+                fn write_line(&mut self, _: &Record<'_>, line: &str) -> io::Result<()> {
+                    self.write_all(line.as_bytes())
+                }
+
+                fn flush_sink(&mut self) -> io::Result<()> {
+                    self.flush()
+                }
+            }
+        )+
+    };
+}
+
+impl_sink_for_writer!(Vec<u8>, io::Stdout, io::Stderr, File, TcpStream);
+
+impl<W: Write + Send> Sink for io::BufWriter<W> {
+    fn write_line(&mut self, _: &Record<'_>, line: &str) -> io::Result<()> {
+        self.write_all(line.as_bytes())
+    }
+
+    fn flush_sink(&mut self) -> io::Result<()> {
+        self.flush()
+    }
+}
+
+impl<S: Sink + ?Sized> Sink for Box<S> {
+    fn write_line(&mut self, record: &Record<'_>, line: &str) -> io::Result<()> {
+        (**self).write_line(record, line)
+    }
+
+    fn flush_sink(&mut self) -> io::Result<()> {
+        (**self).flush_sink()
+    }
+}
+
+impl<S: Sink + ?Sized> Sink for Arc<Mutex<S>> {
+    fn write_line(&mut self, record: &Record<'_>, line: &str) -> io::Result<()> {
+        self.lock().unwrap_or_else(|e| e.into_inner()).write_line(record, line)
+    }
+
+    fn flush_sink(&mut self) -> io::Result<()> {
+        self.lock().unwrap_or_else(|e| e.into_inner()).flush_sink()
+    }
+}
@@ -4,7 +4,6 @@ use super::prelude::*;
 use Options::*;
 
 #[test]
-#[cfg(not(feature = "async"))]
 fn logger_prints() {
     println!();
     let mut l = Logger::new();
@@ -17,7 +16,6 @@ fn logger_prints() {
     }
 
 #[test]
-#[cfg(not(feature = "async"))]
 fn logger_no_header() {
     println!();
     let mut l = Logger::new();
@@ -30,7 +28,6 @@ fn logger_no_header() {
 }
 
 #[test]
-#[cfg(not(feature = "async"))]
 fn logger_plain() {
     println!();
     let mut l = Logger::new();
@@ -43,7 +40,6 @@ fn logger_plain() {
 }
 
 #[test]
-#[cfg(not(feature = "async"))]
 fn logger_basic() {
     println!();
     let mut l = Logger::new();
@@ -56,7 +52,6 @@ fn logger_basic() {
 }
 
 #[test]
-#[cfg(not(feature = "async"))]
 fn logger_no_index() {
     println!();
     let mut l = Logger::new();
@@ -69,7 +64,6 @@ fn logger_no_index() {
 }
 
 #[test]
-#[cfg(not(feature = "async"))]
 fn logger_no_symbol() {
     println!();
     let mut l = Logger::new();
@@ -82,7 +76,6 @@ fn logger_no_symbol() {
 }
 
 #[test]
-#[cfg(not(feature = "async"))]
 fn logger_ns_nc_nb() {
     println!();
     let mut l = Logger::new();
@@ -95,7 +88,6 @@ fn logger_ns_nc_nb() {
 }
 
 #[test]
-#[cfg(not(feature = "async"))]
 fn logger_no_bold() {
     println!();
     let mut l = Logger::new();
@@ -108,7 +100,6 @@ fn logger_no_bold() {
 }
 
 #[test]
-#[cfg(not(feature = "async"))]
 fn logger_file_io() {
     println!();
     let mut l = Logger::new();
@@ -121,7 +112,6 @@ fn logger_file_io() {
 }
 
 #[test]
-#[cfg(not(feature = "async"))]
 fn logger_file_at() {
     println!();
     let mut l = Logger::new();
@@ -134,7 +124,6 @@ fn logger_file_at() {
 }
 
 #[test]
-#[cfg(not(feature = "async"))]
 fn logger_timer() {
     println!();
     let mut l = Logger::new();
@@ -147,7 +136,6 @@ fn logger_timer() {
 }
 
 #[test]
-#[cfg(not(feature = "async"))]
 fn logger_timer_at() {
     println!();
     let mut l = Logger::new();
@@ -163,11 +151,837 @@ fn logger_timer_at() {
 #[cfg(feature = "async")]
 async fn logger_async() {
     println!();
-    let mut l = Logger::new();
+    let mut l = AsyncLogger::new();
     l
         .info("info").await
         .warn("warning").await
         .error("error").await
         .success("success").await
         .critical("critical").await;
+    // The synchronous logger and its builder are still available alongside it.
+    Logger::new().info("sync");
+    let mut b = Logger::builder().symbol(false).build_async().await.unwrap();
+    assert!(!b.shows_symbol());
+    b.set_level(LogLevel::Debug);
+    b.debug("built").await;
+}
+
+#[test]
+fn log_level_order() {
+    assert!(LogLevel::Trace < LogLevel::Debug);
+    assert!(LogLevel::Debug < LogLevel::Info);
+    assert!(LogLevel::Info < LogLevel::Success);
+    assert!(LogLevel::Success < LogLevel::Warn);
+    assert!(LogLevel::Warn < LogLevel::Error);
+    assert!(LogLevel::Error < LogLevel::Critical);
+}
+
+#[test]
+fn logger_level() {
+    println!();
+    let mut l = Logger::new();
+    l.cfg(&[Level(LogLevel::Warn)]).unwrap()
+        .info("info")
+        .warn("warning")
+        .error("error")
+        .success("success")
+        .critical("critical");
+    l.set_level(LogLevel::Critical)
+        .error("error")
+        .critical("critical");
+}
+
+#[test]
+fn logger_verbose() {
+    println!();
+    let mut l = Logger::new();
+    l
+        .trace("hidden")
+        .debug("hidden");
+    l.set_level(LogLevel::Trace)
+        .trace("trace")
+        .debug("debug")
+        .info("info");
+}
+
+#[test]
+fn logger_file_level() {
+    println!();
+    let mut l = Logger::new();
+    l.cfg(&[FileAt(&std::fs::File::create("filelevel.log").unwrap()), FileLevel(LogLevel::Error), StderrLevel(LogLevel::Critical)]).unwrap()
+        .info("info")
+        .warn("warning")
+        .error("error")
+        .success("success")
+        .critical("critical");
+    drop(l);
+    let out = std::fs::read_to_string("filelevel.log").unwrap();
+    assert_eq!(out, "[0000:!] error\n[0001:%] critical\n");
+}
+
+#[test]
+#[cfg(feature = "log")]
+fn logger_facade() {
+    println!();
+    let mut l = Logger::new();
+    l.set_level(LogLevel::Debug);
+    crate::facade::init_with(l);
+    assert_eq!(log::max_level(), log::LevelFilter::Debug);
+    log::trace!("hidden");
+    log::debug!("debug");
+    log::info!("info");
+    log::warn!("warning");
+    log::error!("error");
+    assert!(crate::facade::try_init().is_err());
+}
+
+#[test]
+#[cfg(feature = "tracing")]
+fn logger_tracing_layer() {
+    use tracing_subscriber::prelude::*;
+    println!();
+    let mut l = Logger::new();
+    l.cfg(&[FileAt(&std::fs::File::create("tracing.log").unwrap()), FileOnly]).unwrap();
+    let subscriber = tracing_subscriber::registry().with(crate::layer::ForestryLayer::new(l));
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::debug!("hidden");
+        tracing::info!("info");
+        let outer = tracing::info_span!("outer", id = 7);
+        let _outer = outer.enter();
+        let inner = tracing::info_span!("inner");
+        let _inner = inner.enter();
+        tracing::warn!(status = 404, path = "/", "warning");
+    });
+    let out = std::fs::read_to_string("tracing.log").unwrap();
+    assert_eq!(out, "[0000:*] info\n[0001:~] warning outer{id=7}:inner status=404 path=/\n");
+}
+
+#[test]
+fn shared_logger_threads() {
+    println!();
+    let l = SharedLogger::new(Logger::new());
+    l.cfg(&[FileAt(&std::fs::File::create("shared.log").unwrap()), FileOnly]).unwrap();
+    let handles: Vec<_> = (0..8).map(|i| {
+        let l = l.clone();
+        std::thread::spawn(move || {
+            for j in 0..16 {
+                l.info(&format!("thread {i} message {j}"));
+            }
+        })
+    }).collect();
+    for h in handles {
+        h.join().unwrap();
+    }
+    assert_eq!(l.index(), 128);
+    // Changes made through the lock are seen by the shared index once it is released.
+    l.lock().info("thread main");
+    assert_eq!(l.index(), 129);
+    l.lock().set_index(0x20);
+    assert_eq!(l.index(), 0x20);
+    drop(l);
+    let out = std::fs::read_to_string("shared.log").unwrap();
+    for (i, line) in out.lines().enumerate() {
+        assert!(line.starts_with(&format!("[{:0>4x}:*] thread ", i)));
+    }
+    assert_eq!(out.lines().count(), 129);
+}
+
+#[test]
+fn global_macros() {
+    println!();
+    crate::global::cfg(&[Level(LogLevel::Trace)]).unwrap();
+    let n = 2;
+    crate::trace!("trace {}", n);
+    crate::debug!("debug {}", n);
+    crate::info!("info {n}");
+    crate::warn!("warning {n:>3}");
+    crate::error!("error");
+    crate::success!("success");
+    crate::critical!("critical");
+    assert!(crate::global::set_logger(Logger::new()).is_err());
+
+    // Messages below the level are not formatted.
+    struct Counted<'a>(&'a std::cell::Cell<usize>);
+    impl std::fmt::Display for Counted<'_> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            self.0.set(self.0.get() + 1);
+            f.write_str("counted")
+        }
+    }
+    let formatted = std::cell::Cell::new(0);
+    crate::global::cfg(&[Level(LogLevel::Info)]).unwrap();
+    assert!(!crate::global::enabled(LogLevel::Debug));
+    crate::debug!("{}", Counted(&formatted));
+    assert_eq!(formatted.get(), 0);
+    crate::info!("{}", Counted(&formatted));
+    assert_eq!(formatted.get(), 1);
+}
+
+#[test]
+fn logger_file_append() {
+    println!();
+    let dir = std::path::Path::new("test_logs/append");
+    let _ = std::fs::remove_dir_all(dir);
+    let path = dir.join("nested/append.log");
+    let mut l = Logger::new();
+    l.cfg(&[FilePath(&path), FileOnly]).unwrap()
+        .info("first")
+        .info("second");
+    drop(l);
+    let mut l = Logger::new();
+    l.cfg(&[FileAppend(&path), FileOnly]).unwrap()
+        .info("third");
+    drop(l);
+    let out = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(out, "[0000:*] first\n[0001:*] second\n[0000:*] third\n");
+    let mut l = Logger::new();
+    l.cfg(&[FilePath(&path), FileOnly]).unwrap()
+        .info("fourth");
+    drop(l);
+    let out = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(out, "[0000:*] fourth\n");
+}
+
+#[test]
+fn logger_file_rotation() {
+    println!();
+    let dir = std::path::Path::new("test_logs/rotation");
+    let _ = std::fs::remove_dir_all(dir);
+    let path = dir.join("rotate.log");
+    let mut l = Logger::new();
+    l.cfg(&[FilePath(&path), FileOnly, MaxFileSize(40), KeepFiles(2)]).unwrap();
+    for i in 0..8 {
+        l.info(&format!("line {i}"));
+    }
+    l.flush().unwrap();
+    let read = |p: &str| std::fs::read_to_string(dir.join(p)).unwrap();
+    assert_eq!(read("rotate.log"), "[0006:*] line 6\n[0007:*] line 7\n");
+    assert_eq!(read("rotate.log.1"), "[0004:*] line 4\n[0005:*] line 5\n");
+    assert_eq!(read("rotate.log.2"), "[0002:*] line 2\n[0003:*] line 3\n");
+    assert!(!dir.join("rotate.log.3").exists());
+    // Lowering the limit removes the files above it at the next rotation.
+    l.cfg(&[KeepFiles(1)]).unwrap();
+    l.info("line 8").info("line 9");
+    drop(l);
+    assert_eq!(read("rotate.log.1"), "[0006:*] line 6\n[0007:*] line 7\n");
+    assert!(!dir.join("rotate.log.2").exists());
+}
+
+#[tokio::test]
+#[cfg(feature = "async")]
+async fn logger_async_file_rotation() {
+    println!();
+    let dir = std::path::Path::new("test_logs/async_rotation");
+    let _ = std::fs::remove_dir_all(dir);
+    let path = dir.join("rotate.log");
+    let mut l = AsyncLogger::new();
+    l.cfg(&[FileAppend(&path), FileOnly, MaxFileSize(40), KeepFiles(1)]).await.unwrap();
+    for i in 0..6 {
+        l.info(&format!("line {i}")).await;
+    }
+    // The current file is still buffered, but rotated files are flushed.
+    let read = |p: &str| std::fs::read_to_string(dir.join(p)).unwrap();
+    assert_eq!(read("rotate.log.1"), "[0002:*] line 2\n[0003:*] line 3\n");
+    assert!(!dir.join("rotate.log.2").exists());
+}
+
+#[test]
+fn logger_json() {
+    println!();
+    let mut l = Logger::new();
+    l.cfg(&[StderrFormat(Format::Json)]).unwrap()
+        .info("info")
+        .warn("warning \"quoted\"\n")
+        .critical("critical");
+}
+
+#[test]
+fn logger_json_file() {
+    println!();
+    let mut l = Logger::new();
+    l.cfg(&[FileAt(&std::fs::File::create("json.log").unwrap()), FileOnly, FileFormat(Format::Json), Timer]).unwrap()
+        .info("info")
+        .error("error \"quoted\"\n\u{1}");
+    drop(l);
+    let out = std::fs::read_to_string("json.log").unwrap();
+    let lines: Vec<serde_json::Value> = out
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["index"], 0);
+    assert_eq!(lines[0]["level"], "info");
+    assert_eq!(lines[1]["level"], "error");
+    assert_eq!(lines[1]["message"], "error \"quoted\"\n\u{1}");
+    assert!(lines[1]["elapsed_ms"].is_f64());
+    assert!(lines[1]["timestamp"].as_str().unwrap().ends_with('Z'));
+
+    let mut l = Logger::new();
+    l.cfg(&[FileAt(&std::fs::File::create("json_local.log").unwrap()), FileOnly, FileFormat(Format::Json), Timestamp(Clock::Local)]).unwrap()
+        .info("info");
+    drop(l);
+    let out = std::fs::read_to_string("json_local.log").unwrap();
+    let line: serde_json::Value = serde_json::from_str(out.trim_end()).unwrap();
+    let time = chrono::DateTime::parse_from_rfc3339(line["timestamp"].as_str().unwrap()).unwrap();
+    assert_eq!(time.offset().local_minus_utc(), chrono::Local::now().offset().local_minus_utc());
+}
+
+#[test]
+fn logger_kv() {
+    println!();
+    let mut l = Logger::new();
+    l
+        .info_kv("request done", &[("status", 200.into()), ("ms", 12.5.into())])
+        .warn_kv("slow", &[("path", "/a b".into()), ("cached", false.into())])
+        .log_kv(LogLevel::Error, "failed", &[("reason", String::from("timeout").into())]);
+}
+
+#[test]
+fn logger_kv_file() {
+    println!();
+    let mut l = Logger::new();
+    l.cfg(&[FileAt(&std::fs::File::create("kv.log").unwrap()), FileOnly]).unwrap()
+        .info_kv("done", &[("status", 200.into()), ("path", "/a b".into()), ("empty", "".into())]);
+    l.cfg(&[FileFormat(Format::Json)]).unwrap()
+        .info_kv("done", &[("status", 200.into()), ("ms", 1.5.into()), ("ok", true.into()), ("neg", (-1).into())]);
+    drop(l);
+    let out = std::fs::read_to_string("kv.log").unwrap();
+    let mut lines = out.lines();
+    assert_eq!(lines.next().unwrap(), "[0000:*] done status=200 path=\"/a b\" empty=\"\"");
+    let json: serde_json::Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+    assert_eq!(json["fields"], serde_json::json!({"status": 200, "ms": 1.5, "ok": true, "neg": -1}));
+}
+
+#[test]
+fn logger_timestamp() {
+    println!();
+    let mut l = Logger::new();
+    l.cfg(&[Timestamp(Clock::Utc)]).unwrap()
+        .info("info");
+    l.cfg(&[Timestamp(Clock::Local), Timer]).unwrap()
+        .warn("warning");
+    l.cfg(&[Reset, NoIndex, NoSymbol, TimestampFormat("%H:%M:%S")]).unwrap()
+        .error("error");
+    assert!(l.cfg(&[TimestampFormat("%Q")]).is_err());
+}
+
+#[test]
+fn logger_timestamp_file() {
+    println!();
+    let mut l = Logger::new();
+    l.cfg(&[FileAt(&std::fs::File::create("timestamp.log").unwrap()), FileOnly, TimestampFormat("<%Y>")]).unwrap()
+        .info("info");
+    l.cfg(&[Timestamp(Clock::Utc)]).unwrap();
+    assert_eq!((l.timestamp(), l.timestamp_format()), (Some(Clock::Utc), None));
+    drop(l);
+    let out = std::fs::read_to_string("timestamp.log").unwrap();
+    let year = chrono::Utc::now().format("%Y").to_string();
+    assert_eq!(out, format!("<{year}> [0000:*] info\n"));
+
+    let mut l = Logger::new();
+    l.cfg(&[FileAt(&std::fs::File::create("timestamp_rfc3339.log").unwrap()), FileOnly, TimestampFormat("%H"), Timestamp(Clock::Utc)]).unwrap()
+        .info("info");
+    drop(l);
+    let out = std::fs::read_to_string("timestamp_rfc3339.log").unwrap();
+    let (time, rest) = out.split_once(' ').unwrap();
+    assert!(chrono::DateTime::parse_from_rfc3339(time).is_ok() && time.ends_with('Z'), "{out}");
+    assert_eq!(rest, "[0000:*] info\n");
+}
+
+#[test]
+fn logger_sinks() {
+    use std::sync::{Arc, Mutex};
+    println!();
+    let text = Arc::new(Mutex::new(Vec::new()));
+    let json = Arc::new(Mutex::new(Vec::new()));
+    let mut l = Logger::new();
+    l.cfg(&[StderrLevel(LogLevel::Critical)]).unwrap()
+        .add_sink(text.clone(), LogLevel::Debug, Format::Text)
+        .add_sink(json.clone(), LogLevel::Error, Format::Json)
+        .add_sink(WriteSink(std::io::sink()), LogLevel::Trace, Format::Text)
+        .trace("trace")
+        .debug("debug")
+        .info_kv("info", &[("n", 1.into())])
+        .error("error");
+    let text = String::from_utf8(text.lock().unwrap().clone()).unwrap();
+    assert_eq!(text, "[0001:?] debug\n[0002:*] info n=1\n[0003:!] error\n");
+    let json = String::from_utf8(json.lock().unwrap().clone()).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(json["index"], 3);
+    assert_eq!(json["message"], "error");
+}
+
+#[test]
+fn logger_console_stream() {
+    println!();
+    let mut l = Logger::new();
+    l.cfg(&[Console(Stream::Stdout)]).unwrap()
+        .info("info")
+        .error("error");
+    l.cfg(&[Console(Stream::Split)]).unwrap()
+        .success("success")
+        .warn("warning")
+        .critical("critical");
+}
+
+#[tokio::test]
+#[cfg(feature = "async")]
+async fn logger_async_console_stream() {
+    println!();
+    let mut l = AsyncLogger::new();
+    l.cfg(&[Console(Stream::Split)]).await.unwrap();
+    l
+        .info("info").await
+        .warn("warning").await;
+}
+
+#[test]
+fn logger_color_modes() {
+    println!();
+    let mut l = Logger::new();
+    l.cfg(&[ColorAlways]).unwrap()
+        .info("info")
+        .critical("critical");
+    l.cfg(&[ColorAlways, NoColor]).unwrap()
+        .error("error");
+    l.cfg(&[ColorNever]).unwrap()
+        .warn("warning");
+    l.cfg(&[ColorAuto]).unwrap()
+        .success("success");
+}
+
+#[test]
+fn logger_themes() {
+    println!();
+    let mut theme = Theme::emoji();
+    theme.warn = LevelStyle::new("WARN").fg(crate::theme::Color::Magenta).italic().underline();
+    let buf = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let mut l = Logger::new();
+    l.add_sink(buf.clone(), LogLevel::Info, Format::Text);
+    l.cfg(&[ColorAlways, UseTheme(&theme)]).unwrap()
+        .info("info")
+        .warn("warning");
+    l.cfg(&[UseTheme(&Theme::colorblind())]).unwrap()
+        .success("success")
+        .error("error");
+    assert_eq!(
+        String::from_utf8(buf.lock().unwrap().clone()).unwrap(),
+        "[0000:💬] info\n[0001:WARN] warning\n[0002:+] success\n[0003:!] error\n"
+    );
+}
+
+#[test]
+fn logger_pattern() {
+    println!();
+    let buf = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let mut l = Logger::new();
+    l.add_sink(buf.clone(), LogLevel::Info, Format::Text);
+    l.cfg(&[Pattern("{level:>5} [{index:04x}] {{{symbol:-^3}}} {msg}")]).unwrap()
+        .info("info")
+        .warn_kv("warning", &[("code", 7.into())]);
+    l.cfg(&[Pattern("{index:>3d}|{level:.1}|{fields}|{msg}")]).unwrap()
+        .error_kv("error", &[("a", 1.into()), ("b", "x y".into())]);
+    l.cfg(&[Pattern("{time:%Y} {elapsed}{target}{msg}")]).unwrap()
+        .success("success");
+    let out = String::from_utf8(buf.lock().unwrap().clone()).unwrap();
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines[0], " INFO [0000] {-*-} info");
+    assert_eq!(lines[1], " WARN [0001] {-~-} warning code=7");
+    assert_eq!(lines[2], "  2|E|a=1 b=\"x y\"|error");
+    assert_eq!(&lines[3][4..], " success");
+
+    for bad in ["{nope}", "{msg", "}", "{msg:x}", "{index:04q}", "{time:%Q}", "{elapsed:.}"] {
+        assert_eq!(l.cfg(&[Pattern(bad)]).err().map(|e| e.kind()), Some(std::io::ErrorKind::InvalidInput), "{bad}");
+    }
+}
+
+#[test]
+fn logger_index() {
+    println!();
+    let buf = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let mut l = Logger::new();
+    l.add_sink(buf.clone(), LogLevel::Info, Format::Text);
+    l.set_index(0x1_ffff);
+    assert_eq!(l.index(), 0xffff);
+    l.info("last").info("wrapped");
+    assert_eq!(l.index(), 2);
+
+    l.cfg(&[WideIndex, IndexWidth(8), IndexRadix(Radix::Decimal)]).unwrap()
+        .set_index(0x1_ffff)
+        .info("wide");
+    assert_eq!(l.index(), 0x2_0000);
+    l.reset_index()
+        .info("reset");
+    assert_eq!(
+        String::from_utf8(buf.lock().unwrap().clone()).unwrap(),
+        "[ffff:*] last\n[0000:~] Log index overflowed; log index may be inaccurate.\n[0001:*] wrapped\n[00131071:*] wide\n[00000000:*] reset\n"
+    );
+}
+
+#[tokio::test]
+#[cfg(feature = "async")]
+async fn logger_async_index() {
+    println!();
+    let buf = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let mut l = AsyncLogger::new();
+    l.add_sink(buf.clone(), LogLevel::Info, Format::Text);
+    l.set_index(0xffff);
+    l.info("last").await.info("wrapped").await;
+    assert_eq!(l.index(), 2);
+    assert_eq!(
+        String::from_utf8(buf.lock().unwrap().clone()).unwrap(),
+        "[ffff:*] last\n[0000:~] Log index overflowed; log index may be inaccurate.\n[0001:*] wrapped\n"
+    );
+}
+
+#[test]
+fn logger_builder() {
+    println!();
+    let buf = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let mut l = Logger::builder()
+        .symbol(false)
+        .bold(false)
+        .file_path("test_logs/builder.log")
+        .file_only(true)
+        .timestamp(Clock::Local)
+        .index_radix(Radix::Decimal)
+        .start_index(42)
+        .level(LogLevel::Debug)
+        .sink(buf.clone(), LogLevel::Trace, Format::Text)
+        .build()
+        .unwrap();
+    assert!(l.shows_index() && !l.shows_symbol() && l.shows_color() && !l.shows_bold());
+    assert!(l.logs_to_file() && l.file_only());
+    assert_eq!(l.file_path(), Some(std::path::Path::new("test_logs/builder.log")));
+    assert_eq!(l.timestamp(), Some(Clock::Local));
+    assert_eq!((l.index(), l.index_radix(), l.stderr_level()), (42, Radix::Decimal, LogLevel::Debug));
+    l.debug("debug");
+
+    l.cfg(&[Symbol, Bold, NoTimestamp, NoFile]).unwrap();
+    assert!(l.shows_symbol() && l.shows_bold() && !l.logs_to_file() && !l.file_only());
+    assert_eq!(l.timestamp(), None);
+    l.info("info");
+    assert_eq!(std::fs::read_to_string("test_logs/builder.log").unwrap().lines().count(), 1);
+
+    l.cfg(&[NoIndex, Timer, Pattern("{msg}"), StderrLevel(LogLevel::Error), Reset]).unwrap();
+    assert!(l.shows_index() && !l.shows_timer() && l.timer().is_none());
+    assert_eq!((l.pattern(), l.stderr_level(), l.index()), (None, LogLevel::Info, 44));
+    l.warn("warning");
+    let out = String::from_utf8(buf.lock().unwrap().clone()).unwrap();
+    assert!(out.ends_with("[0042] debug\n[0043:*] info\n[002c:~] warning\n"), "{out}");
+}
+
+#[test]
+fn logger_from_env() {
+    use std::collections::HashMap;
+    use std::ffi::OsString;
+    println!();
+    let vars: HashMap<&str, &str> = HashMap::from([
+        ("FORESTRY_LEVEL", "Debug"),
+        ("FORESTRY_FILE_LEVEL", "warning"),
+        ("FORESTRY_COLOR", "never"),
+        ("FORESTRY_FORMAT", "json"),
+        ("FORESTRY_STDERR_FORMAT", "text"),
+        ("FORESTRY_CONSOLE", "split"),
+        ("FORESTRY_TIMESTAMP", ""),
+    ]);
+    let l = Logger::builder()
+        .timer()
+        .env_with(|var| vars.get(var).map(OsString::from))
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!((l.stderr_level(), l.file_level()), (LogLevel::Debug, LogLevel::Warn));
+    assert_eq!((l.stderr_format(), l.file_format()), (Format::Text, Format::Json));
+    assert_eq!((l.color_mode(), l.console(), l.timestamp()), (ColorMode::Never, Stream::Split, None));
+    assert!(l.shows_timer());
+
+    for (var, value) in [("FORESTRY_LEVEL", "loud"), ("FORESTRY_PATTERN", "{nope}")] {
+        let err = Logger::builder().env_with(|v| (v == var).then(|| value.into())).err().unwrap();
+        assert!(matches!(err, crate::env::EnvError::Invalid { var: v, .. } if v == var), "{err}");
+    }
+    assert_eq!("CRITICAL".parse::<LogLevel>(), Ok(LogLevel::Critical));
+    assert_eq!("xml".parse::<Format>().unwrap_err().value, "xml");
+}
+
+#[test]
+fn logger_write_failure() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    struct Broken;
+    impl Sink for Broken {
+        fn write_line(&mut self, _: &Record<'_>, _: &str) -> std::io::Result<()> {
+            Err(std::io::ErrorKind::BrokenPipe.into())
+        }
+    }
+    println!();
+    let mut l = Logger::new();
+    l.cfg(&[StderrLevel(LogLevel::Critical)]).unwrap();
+    l.add_sink(Vec::new(), LogLevel::Info, Format::Text);
+    l.add_sink(Broken, LogLevel::Info, Format::Text);
+    assert_eq!(l.write_failure(), WriteFailure::Stderr);
+    let err = l.try_info("info").err().unwrap();
+    assert!(matches!(&err, crate::Error::Sink(e) if e.kind() == std::io::ErrorKind::BrokenPipe), "{err}");
+    assert_eq!(l.index(), 1);
+    assert!(catch_unwind(AssertUnwindSafe(|| { l.warn("on stderr").error("flushed, on stderr"); })).is_ok());
+
+    l.cfg(&[OnWriteFailure(WriteFailure::Panic)]).unwrap();
+    assert!(catch_unwind(AssertUnwindSafe(|| { l.warn("warning"); })).is_err());
+    l.cfg(&[OnWriteFailure(WriteFailure::Ignore)]).unwrap().error("ignored");
+    l.clear_sinks();
+    assert!(l.try_critical("critical").is_ok());
+    assert_eq!(l.index(), 6);
+    assert_eq!("Ignore".parse(), Ok(WriteFailure::Ignore));
+}
+
+#[test]
+fn logger_flush() {
+    use std::time::Duration;
+    println!();
+    let path = std::path::Path::new("test_logs/flush.log");
+    let read = || std::fs::read_to_string(path).unwrap();
+    let mut l = Logger::new();
+    l.cfg(&[FilePath(path), FileOnly, NoIndex, NoSymbol]).unwrap();
+    assert_eq!((l.flush_level(), l.flush_interval()), (LogLevel::Error, None));
+    l.info("a");
+    assert_eq!(read(), "");
+    l.error("b");
+    assert_eq!(read(), "a\nb\n");
+    l.info("c").flush().unwrap();
+    assert_eq!(read(), "a\nb\nc\n");
+    l.cfg(&[FlushLevel(LogLevel::Critical), FlushInterval(Duration::ZERO)]).unwrap().info("d");
+    assert_eq!(read(), "a\nb\nc\nd\n");
+    l.cfg(&[NoFlushInterval]).unwrap().error("e");
+    assert_eq!(read(), "a\nb\nc\nd\n");
+    drop(l);
+    assert_eq!(read(), "a\nb\nc\nd\ne\n");
+}
+
+#[tokio::test]
+#[cfg(feature = "async")]
+async fn logger_async_flush() {
+    println!();
+    let path = std::path::Path::new("test_logs/async_flush.log");
+    let read = || std::fs::read_to_string(path).unwrap();
+    let mut l = AsyncLogger::new();
+    l.cfg(&[FilePath(path), FileOnly, NoIndex, NoSymbol]).await.unwrap();
+    l.info("a").await;
+    l.error("b").await;
+    assert_eq!(read(), "a\nb\n");
+    l.info("c").await.flush().await.unwrap();
+    assert_eq!(read(), "a\nb\nc\n");
+    l.info("d").await.shutdown().await.unwrap();
+    assert_eq!(read(), "a\nb\nc\nd\n");
+    assert!(!l.logs_to_file());
+}
+
+#[test]
+fn logger_panic_hook() {
+    use std::sync::{Arc, Mutex};
+    println!();
+    let out = Arc::new(Mutex::new(Vec::new()));
+    let mut l = Logger::new();
+    l.cfg(&[NoIndex, NoSymbol]).unwrap().add_sink(out.clone(), LogLevel::Critical, Format::Text);
+    let log = SharedLogger::new(l);
+    crate::install_panic_hook(log.clone());
+    let panicked = std::thread::Builder::new()
+        .name("worker".into())
+        .spawn(|| panic!("boom {}", 1))
+        .unwrap()
+        .join();
+    let _ = std::panic::take_hook();
+    assert!(panicked.is_err());
+    // Other tests may panic while the hook is installed.
+    let text = String::from_utf8(out.lock().unwrap().clone()).unwrap();
+    let line = text.lines().find(|l| l.contains("boom")).unwrap();
+    assert!(line.starts_with("thread 'worker' panicked at src/tests.rs:"), "{line}");
+    assert!(line.ends_with(": boom 1"), "{line}");
+    assert!(log.index() >= 1);
+}
+
+#[test]
+fn logger_non_blocking() {
+    use crate::non_blocking::{NonBlocking, Overflow};
+    use std::sync::mpsc::Sender;
+    use std::sync::{Arc, Mutex};
+    println!();
+    let sink_logger = |out: &Arc<Mutex<Vec<u8>>>| {
+        let mut l = Logger::new();
+        l.cfg(&[FileOnly, NoIndex, NoSymbol]).unwrap().add_sink(out.clone(), LogLevel::Debug, Format::Text);
+        l
+    };
+    let lines = |out: &Arc<Mutex<Vec<u8>>>| String::from_utf8(out.lock().unwrap().clone()).unwrap();
+
+    let out = Arc::new(Mutex::new(Vec::new()));
+    let (log, guard) = NonBlocking::new(sink_logger(&out));
+    log.trace("not queued").info_kv("first", &[("n", 1.into())]).flush().unwrap();
+    assert_eq!(lines(&out), "first n=1\n");
+    let handles: Vec<_> = (0..4).map(|i| {
+        let log = log.clone();
+        std::thread::spawn(move || {
+            for j in 0..25 {
+                log.debug(&format!("{i}:{j}"));
+            }
+        })
+    }).collect();
+    for h in handles {
+        h.join().unwrap();
+    }
+    drop(guard);
+    assert_eq!(lines(&out).lines().count(), 101);
+    log.info("after the guard");
+    assert_eq!(log.dropped(), 1);
+
+    // The worker blocks writing "a" while the test holds the sink; "a" counts against the
+    // capacity until it is written, so only one more message fits.
+    struct Gate(Sender<()>, Arc<Mutex<Vec<u8>>>);
+    impl Sink for Gate {
+        fn write_line(&mut self, _: &Record<'_>, line: &str) -> std::io::Result<()> {
+            let _ = self.0.send(());
+            self.1.lock().unwrap().extend_from_slice(line.as_bytes());
+            Ok(())
+        }
+    }
+    for (overflow, written) in [(Overflow::DropNewest, "a\nb\n"), (Overflow::DropOldest, "a\nd\n")] {
+        let out = Arc::new(Mutex::new(Vec::new()));
+        let (entered, wait) = std::sync::mpsc::channel();
+        let mut l = Logger::new();
+        l.cfg(&[FileOnly, NoIndex, NoSymbol]).unwrap().add_sink(Gate(entered, out.clone()), LogLevel::Debug, Format::Text);
+        let (log, guard) = NonBlocking::with_capacity(l, 2, overflow);
+        let held = out.lock().unwrap();
+        log.info("a");
+        wait.recv().unwrap();
+        for s in ["b", "c", "d"] {
+            log.info(s);
+        }
+        drop(held);
+        drop(guard);
+        assert_eq!(lines(&out), written, "{overflow:?}");
+        assert_eq!(log.dropped(), 2, "{overflow:?}");
+    }
+}
+
+#[test]
+#[cfg(feature = "config")]
+fn logger_config() {
+    use crate::config::{Config, ConfigError};
+    println!();
+    std::fs::create_dir_all("test_logs").unwrap();
+    std::fs::write("test_logs/forestry.toml", r#"
+        level = "debug"
+        color = "never"
+        theme = "emoji"
+        symbol = false
+
+        [file]
+        path = "test_logs/config.log"
+        level = "warn"
+        format = "json"
+        max_size = 4096
+        rotate = "daily"
+
+        [[sinks]]
+        kind = "file"
+        path = "test_logs/config_sink.log"
+        level = "error"
+    "#).unwrap();
+    let mut l = Logger::from_config_file("test_logs/forestry.toml").unwrap();
+    assert_eq!((l.stderr_level(), l.file_level()), (LogLevel::Debug, LogLevel::Warn));
+    assert_eq!((l.file_format(), l.color_mode()), (Format::Json, ColorMode::Never));
+    assert_eq!((l.max_file_size(), l.rotation()), (Some(4096), Rotation::Daily));
+    assert_eq!(l.theme(), &Theme::emoji());
+    assert!(l.logs_to_file() && !l.shows_symbol());
+    l.error("to every output");
+
+    let json = Config::from_json(r#"{"level": "warn", "sinks": [{"kind": "stdout"}]}"#).unwrap();
+    assert_eq!(json.sinks.len(), 1);
+    json.apply_to(&mut l).unwrap();
+    assert_eq!((l.stderr_level(), l.logs_to_file(), l.index()), (LogLevel::Warn, true, 1));
+
+    let err = Config::from_toml("level = 'loud'").unwrap().builder().err().unwrap();
+    assert!(matches!(err, ConfigError::Invalid { key: "level", .. }), "{err}");
+    assert!(matches!(Config::from_toml("levle = 'info'"), Err(ConfigError::Toml(_))));
+    let err = Config::from_toml("[[sinks]]\nkind = 'file'").unwrap().builder().err().unwrap();
+    assert!(matches!(err, ConfigError::Invalid { key: "sinks.kind", .. }), "{err}");
+}
+
+#[test]
+#[cfg(feature = "config")]
+fn logger_config_reload() {
+    use crate::config::Config;
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+    println!();
+    std::fs::create_dir_all("test_logs").unwrap();
+    let (config, file) = ("test_logs/reload.toml", "test_logs/reload.log");
+    let settings = |level: &str, path: &str| format!("level = '{level}'\n[file]\npath = '{path}'\nappend = false\nkeep = 2");
+    std::fs::write(config, settings("info", file)).unwrap();
+    let out = Arc::new(Mutex::new(Vec::new()));
+    let mut l = Logger::from_config_file(config).unwrap();
+    l.cfg(&[NoIndex, NoSymbol, Timer]).unwrap().add_sink(out.clone(), LogLevel::Info, Format::Text);
+    let timer = l.timer();
+    l.info("before");
+
+    std::fs::write(config, settings("debug", file)).unwrap();
+    Config::load(config).unwrap().apply_to(&mut l).unwrap();
+    assert_eq!((l.stderr_level(), l.timer(), l.file_path()), (LogLevel::Debug, timer, Some(Path::new(file))));
+    assert!(!l.shows_index() && !l.shows_symbol());
+    l.info("after").flush().unwrap();
+    let sink = String::from_utf8(out.lock().unwrap().clone()).unwrap();
+    assert!(sink.contains("before") && sink.contains("after"), "{sink}");
+    let written = std::fs::read_to_string(file).unwrap();
+    assert!(written.contains("before") && written.contains("after"), "{written}");
+
+    let moved = "test_logs/reload_moved.log";
+    std::fs::write(config, settings("debug", moved)).unwrap();
+    Config::load(config).unwrap().apply_to(&mut l).unwrap();
+    l.info("moved").flush().unwrap();
+    assert_eq!(l.file_path(), Some(Path::new(moved)));
+    assert!(!std::fs::read_to_string(file).unwrap().contains("moved"));
+    assert!(std::fs::read_to_string(moved).unwrap().contains("moved"));
+
+    Config::from_toml("timestamp_format = '%H:%M'").unwrap().apply_to(&mut l).unwrap();
+    assert_eq!(l.timestamp_format(), Some("%H:%M"));
+    Config::from_toml("timestamp = 'utc'").unwrap().apply_to(&mut l).unwrap();
+    assert_eq!((l.timestamp(), l.timestamp_format()), (Some(Clock::Utc), None));
+}
+
+#[tokio::test]
+#[cfg(all(feature = "config", feature = "async"))]
+async fn logger_async_config_file() {
+    use crate::config::Config;
+    use std::path::Path;
+    println!();
+    std::fs::create_dir_all("test_logs").unwrap();
+    let (first, second) = ("test_logs/async_config.log", "test_logs/async_config_cfg.log");
+    let mut l = AsyncLogger::new();
+    let config = Config::from_toml(&format!("[file]\npath = '{first}'\nappend = false\nonly = true")).unwrap();
+    config.apply_to_async(&mut l).await.unwrap();
+    l.try_info("through apply_to_async").await.unwrap().flush().await.unwrap();
+    assert!(std::fs::read_to_string(first).unwrap().contains("through apply_to_async"));
+
+    l.cfg(&[FilePath(Path::new(second))]).await.unwrap();
+    l.try_info("through cfg").await.unwrap().flush().await.unwrap();
+    assert_eq!(l.file_path(), Some(Path::new(second)));
+    assert!(std::fs::read_to_string(second).unwrap().contains("through cfg"));
+}
+
+#[test]
+#[cfg(feature = "watch")]
+fn logger_watch() {
+    use std::time::{Duration, Instant};
+    println!();
+    std::fs::create_dir_all("test_logs").unwrap();
+    let path = "test_logs/watch.toml";
+    std::fs::write(path, "level = 'info'").unwrap();
+    let log = SharedLogger::new(Logger::from_config_file(path).unwrap());
+    let watcher = crate::watch::watch_every(path, log.clone(), Duration::from_millis(10));
+    log.debug("hidden");
+    std::fs::write(path, "level = 'debug'\ncolor = 'never'").unwrap();
+    let start = Instant::now();
+    while log.lock().stderr_level() != LogLevel::Debug {
+        assert!(start.elapsed() < Duration::from_secs(5), "config was not reloaded");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    log.debug("visible");
+    watcher.stop();
 }
@@ -0,0 +1,203 @@
+//! Colours and symbols for each log level.
+//!
+//! A [Theme] holds a [LevelStyle] for every [LogLevel], and is set on a logger with
+//! [Options::UseTheme](crate::logs::Options::UseTheme). Besides the default
+//! [classic](Theme::classic) theme, forestry ships a [colour-blind friendly](Theme::colorblind)
+//! theme and an [emoji](Theme::emoji) theme.
+//!
+//! The header (index, symbol and timers) always takes the level's colours and is bold;
+//! the message takes its colours and, if set, bold, italic and underline.
+//! `NoColor` and `NoBold` still remove colours and text styles respectively.
+//!
+//! # Example
+//! ```rust
+//!  use forestry::prelude::*;
+//!  use forestry::theme::Color;
+//!  let mut theme = Theme::colorblind();
+//!  theme.warn = LevelStyle::new("WARN").fg(Color::Magenta).underline();
+//!  let mut log = Logger::new();
+//!  log.cfg(&[Options::UseTheme(&theme)]).unwrap()
+//!      .warn("warning");   // Output: [0000:WARN] warning
+//! ```
+
+pub use colored::Color;
+
+use crate::logs::LogLevel;
+
+/**
+    The symbol, colours and text styles of one log level.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LevelStyle {
+    /// The symbol shown in the header.
+    pub symbol: String,
+    /// The foreground colour.
+    pub fg: Option<Color>,
+    /// The background colour.
+    pub bg: Option<Color>,
+    /// Whether the message is bold.
+    pub bold: bool,
+    /// Whether the message is italic.
+    pub italic: bool,
+    /// Whether the message is underlined.
+    pub underline: bool,
+}
+
+impl LevelStyle {
+    /// A style with the given symbol and no colours or text styles.
+    pub fn new(symbol: &str) -> Self {
+        LevelStyle {
+            symbol: symbol.to_string(),
+            fg: None,
+            bg: None,
+            bold: false,
+            italic: false,
+            underline: false,
+        }
+    }
+
+    /// Set the foreground colour.
+    pub fn fg(mut self, color: Color) -> Self {
+        self.fg = Some(color);
+        self
+    }
+
+    /// Set the background colour.
+    pub fn bg(mut self, color: Color) -> Self {
+        self.bg = Some(color);
+        self
+    }
+
+    /// Make the message bold.
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    /// Make the message italic.
+    pub fn italic(mut self) -> Self {
+        self.italic = true;
+        self
+    }
+
+    /// Underline the message.
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+}
+
+/**
+    The styles of every log level.
+
+    The default theme is [Theme::classic].
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    /// The style of `Trace` messages.
+    pub trace: LevelStyle,
+    /// The style of `Debug` messages.
+    pub debug: LevelStyle,
+    /// The style of `Info` messages.
+    pub info: LevelStyle,
+    /// The style of `Success` messages.
+    pub success: LevelStyle,
+    /// The style of `Warn` messages.
+    pub warn: LevelStyle,
+    /// The style of `Error` messages.
+    pub error: LevelStyle,
+    /// The style of `Critical` messages.
+    pub critical: LevelStyle,
+}
+
+impl Theme {
+    /**
+        The classic forestry theme.
+
+        | Level    | Symbol | Colour       |
+        |----------|--------|--------------|
+        | Trace    | `.`    | grey         |
+        | Debug    | `?`    | cyan         |
+        | Info     | `*`    | blue         |
+        | Success  | `+`    | green        |
+        | Warn     | `~`    | yellow       |
+        | Error    | `!`    | red          |
+        | Critical | `%`    | white on red |
+     */
+    pub fn classic() -> Self {
+        Theme {
+            trace: LevelStyle::new(".").fg(Color::BrightBlack),
+            debug: LevelStyle::new("?").fg(Color::Cyan),
+            info: LevelStyle::new("*").fg(Color::Blue),
+            success: LevelStyle::new("+").fg(Color::Green).bold(),
+            warn: LevelStyle::new("~").fg(Color::Yellow),
+            error: LevelStyle::new("!").fg(Color::Red).bold(),
+            critical: LevelStyle::new("%").fg(Color::White).bg(Color::Red).bold(),
+        }
+    }
+
+    /**
+        A theme for colour-blind users, based on the Okabe-Ito palette.
+
+        Levels which are easily confused, such as `Success` and `Error`, also
+        differ in symbol and text style, so they remain distinct without colour.
+        This theme uses 24-bit colour.
+     */
+    pub fn colorblind() -> Self {
+        let rgb = |r, g, b| Color::TrueColor { r, g, b };
+        Theme {
+            trace: LevelStyle::new(".").fg(Color::BrightBlack),
+            debug: LevelStyle::new("?").fg(rgb(86, 180, 233)),
+            info: LevelStyle::new("*").fg(rgb(0, 114, 178)),
+            success: LevelStyle::new("+").fg(rgb(0, 158, 115)).italic(),
+            warn: LevelStyle::new("~").fg(rgb(230, 159, 0)),
+            error: LevelStyle::new("!").fg(rgb(213, 94, 0)).bold().underline(),
+            critical: LevelStyle::new("%").fg(Color::White).bg(rgb(213, 94, 0)).bold().underline(),
+        }
+    }
+
+    /// The classic theme's colours, with emoji symbols.
+    pub fn emoji() -> Self {
+        let mut theme = Theme::classic();
+        theme.trace.symbol = "🔍".into();
+        theme.debug.symbol = "🐛".into();
+        theme.info.symbol = "💬".into();
+        theme.success.symbol = "✅".into();
+        theme.warn.symbol = "🚧".into();
+        theme.error.symbol = "❌".into();
+        theme.critical.symbol = "🔥".into();
+        theme
+    }
+
+    /// The style of the given level.
+    pub fn get(&self, lvl: LogLevel) -> &LevelStyle {
+        match lvl {
+            LogLevel::Trace => &self.trace,
+            LogLevel::Debug => &self.debug,
+            LogLevel::Info => &self.info,
+            LogLevel::Success => &self.success,
+            LogLevel::Warn => &self.warn,
+            LogLevel::Error => &self.error,
+            LogLevel::Critical => &self.critical,
+        }
+    }
+
+    /// The style of the given level, mutably.
+    pub fn get_mut(&mut self, lvl: LogLevel) -> &mut LevelStyle {
+        match lvl {
+            LogLevel::Trace => &mut self.trace,
+            LogLevel::Debug => &mut self.debug,
+            LogLevel::Info => &mut self.info,
+            LogLevel::Success => &mut self.success,
+            LogLevel::Warn => &mut self.warn,
+            LogLevel::Error => &mut self.error,
+            LogLevel::Critical => &mut self.critical,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::classic()
+    }
+}
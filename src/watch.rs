@@ -0,0 +1,90 @@
+//! Reloading a config file while the program runs.
+//!
+//! Requires the `watch` feature. [watch()] starts a background thread which checks a config
+//! file for changes and re-applies it to a [SharedLogger] with
+//! [Config::apply_to](crate::config::Config::apply_to), so that, for example, debug output can
+//! be turned on in production by editing `forestry.toml`. A file which fails to load is
+//! reported with a warning through the logger, which keeps its previous settings.
+//!
+//! The file is polled, by its modification time and size, so no platform file-watching API is
+//! needed. Watching stops when the returned [Watcher] is dropped.
+//!
+//! # Example
+//! ```rust,no_run
+//!  use forestry::prelude::*;
+//!  let log = SharedLogger::new(Logger::from_config_file("forestry.toml").unwrap());
+//!  let _watcher = forestry::watch::watch("forestry.toml", log.clone());
+//!  log.info("edit forestry.toml to change the level");
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+use crate::config::Config;
+use crate::shared::SharedLogger;
+
+/**
+    A handle to a thread watching a config file.
+
+    Dropping it stops the thread and waits for it to finish.
+ */
+pub struct Watcher {
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Watcher {
+    /// Stop watching.
+    pub fn stop(self) {}
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        // Dropping the sender wakes the thread, which then exits.
+        self.stop.take();
+        if let Some(t) = self.thread.take() {
+            let _ = t.join();
+        }
+    }
+}
+
+/// The modification time and size of a file, or `None` if it cannot be read.
+fn stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+/// Watch a config file, checking it for changes every second.
+pub fn watch(path: impl Into<PathBuf>, logger: SharedLogger) -> Watcher {
+    watch_every(path, logger, Duration::from_secs(1))
+}
+
+/**
+    Watch a config file, checking it for changes at the given interval.
+
+    The file is re-applied whenever its modification time or size changes. While it is
+    missing, for example in the middle of an editor's save, it is not re-applied.
+ */
+pub fn watch_every(path: impl Into<PathBuf>, logger: SharedLogger, interval: Duration) -> Watcher {
+    let path = path.into();
+    let (stop, rx) = mpsc::channel::<()>();
+    let mut last = stamp(&path);
+    let thread = thread::spawn(move || {
+        while let Err(RecvTimeoutError::Timeout) = rx.recv_timeout(interval) {
+            let now = stamp(&path);
+            if now.is_none() || now == last {
+                continue;
+            }
+            last = now;
+            let reloaded = Config::load(&path).and_then(|c| c.apply_to(&mut logger.lock()));
+            match reloaded {
+                Ok(()) => logger.info(&format!("reloaded config from {}", path.display())),
+                Err(e) => logger.warn(&format!("failed to reload config from {}: {}", path.display(), e)),
+            };
+        }
+    });
+    Watcher { stop: Some(stop), thread: Some(thread) }
+}